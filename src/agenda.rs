@@ -0,0 +1,57 @@
+use chrono::NaiveDate;
+
+use crate::categories;
+use crate::config;
+use crate::{format_delta_to, Event};
+
+/// Print the events grouped under day headings, like
+///
+/// Today
+///   Starting course work (school)
+/// Friday 2023-05-12 - in 2 days
+///   Juhannusaatto (holidays)
+///
+/// The events have to be sorted by date
+pub fn print_agenda(events: &[&Event], today: NaiveDate) {
+    for line in agenda_lines(events, today) {
+        println!("{}", line);
+    }
+}
+
+/// The lines of the agenda, for printing or for the tooltips of the bar
+pub fn agenda_lines(events: &[&Event], today: NaiveDate) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut previous_date = None;
+    for event in events {
+        if previous_date != Some(event.date) {
+            lines.push(day_heading(event.date, today));
+            previous_date = Some(event.date);
+        }
+        if event.category.is_empty() {
+            lines.push(format!("  {}{}", event.description, event.tags_display()));
+        } else {
+            let category = categories::get().display(&event.category);
+            lines.push(format!(
+                "  {} ({}){}",
+                event.description,
+                category,
+                event.tags_display()
+            ));
+        }
+    }
+    lines
+}
+
+/// Today, Tomorrow, Yesterday or the weekday, date and delta like Friday 2023-05-12 - in 2 days
+pub fn day_heading(date: NaiveDate, today: NaiveDate) -> String {
+    let config = config::get();
+    let delta = (date - today).num_days();
+    match config.locale.relative_day(delta) {
+        Some(day) => day.to_string(),
+        None => format!(
+            "{} - {}",
+            config.locale.weekday_and_date(date, &config.date_format),
+            format_delta_to(date, today)
+        ),
+    }
+}
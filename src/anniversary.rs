@@ -0,0 +1,197 @@
+use chrono::{Datelike, NaiveDate};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::categories;
+use crate::config;
+use crate::precision::Precision;
+use crate::Event;
+
+/// Whether past events are shown with their anniversaries, decided in main with enable
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Show past events as years ago and the next anniversary
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+/// With --anniversaries or anniversaries = true in the config
+pub fn enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed) || config::get().anniversaries
+}
+
+/// How February 29 is handled in other years
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LeapDay {
+    /// On February 28
+    Feb28,
+    /// On March 1
+    Mar1,
+    /// Only in leap years
+    Skip,
+}
+
+impl LeapDay {
+    pub fn parse(rule: &str) -> Option<Self> {
+        match rule {
+            "feb28" => Some(LeapDay::Feb28),
+            "mar1" => Some(LeapDay::Mar1),
+            "skip" => Some(LeapDay::Skip),
+            _ => None,
+        }
+    }
+
+    /// The anniversary of the date in the year with this rule, see in_year
+    fn in_year(self, date: NaiveDate, year: i32) -> Option<NaiveDate> {
+        date.with_year(year).or_else(|| match self {
+            LeapDay::Feb28 => NaiveDate::from_ymd_opt(year, 2, 28),
+            LeapDay::Mar1 => NaiveDate::from_ymd_opt(year, 3, 1),
+            LeapDay::Skip => None,
+        })
+    }
+
+    /// Whole years from the date to today with this rule, see years_since
+    fn years_since(self, date: NaiveDate, today: NaiveDate) -> i32 {
+        let years = today.year() - date.year();
+        match self.in_year(date, today.year()) {
+            Some(anniversary) if anniversary > today => years - 1,
+            Some(_) => years,
+            // A skipped February 29 is counted as passed on March 1
+            None if (today.month(), today.day()) < (3, 1) => years - 1,
+            None => years,
+        }
+    }
+}
+
+/// The anniversary of the date in the year. February 29 follows leap_day of the config
+/// in other years, None if it's skipped
+pub fn in_year(date: NaiveDate, year: i32) -> Option<NaiveDate> {
+    config::get().leap_day.in_year(date, year)
+}
+
+/// Whole years from the date to today
+pub fn years_since(date: NaiveDate, today: NaiveDate) -> i32 {
+    config::get().leap_day.years_since(date, today)
+}
+
+/// The next anniversary of the date, today included, and which anniversary it is.
+/// A skipped February 29 can be up to eight years away
+pub fn next(date: NaiveDate, today: NaiveDate) -> Option<(NaiveDate, i32)> {
+    let anniversary = (today.year()..=today.year() + 8)
+        .filter_map(|year| in_year(date, year))
+        .find(|anniversary| *anniversary >= today)?;
+    Some((anniversary, anniversary.year() - date.year()))
+}
+
+/// The delta of a past event, like 37 years ago, 38th anniversary in 120 days, or
+/// 1st anniversary in 3 days within the first year. None for today and later events,
+/// they are shown as usual
+pub fn delta(date: NaiveDate, today: NaiveDate) -> Option<String> {
+    if date >= today {
+        return None;
+    }
+    let years = years_since(date, today);
+    let (anniversary, nth) = next(date, today)?;
+    Some(
+        config::get()
+            .locale
+            .anniversary(years, nth, (anniversary - today).num_days()),
+    )
+}
+
+/// Print the events of previous years on the month and day, newest first, like
+///
+/// 2010 - 13 years ago
+///   Go (sport)
+///
+/// February 29 events are shown on the day of leap_day in other years.
+/// Returns the number of events
+pub fn print_on_this_day(events: &[Event], month: u32, day: u32, today: NaiveDate) -> usize {
+    let year = today.year();
+    let mut matching: Vec<&Event> = events
+        .iter()
+        .filter(|e| e.precision == Precision::Day && e.date.year() < year)
+        .filter(|e| {
+            (e.date.month(), e.date.day()) == (month, day)
+                || in_year(e.date, year).is_some_and(|d| (d.month(), d.day()) == (month, day))
+        })
+        .collect();
+    matching.sort_by_key(|e| std::cmp::Reverse(e.date));
+
+    let registry = categories::get();
+    let locale = config::get().locale;
+    let mut previous_year = None;
+    for event in matching.iter() {
+        if previous_year != Some(event.date.year()) {
+            println!(
+                "{} - {}",
+                event.date.year(),
+                locale.years_ago(year - event.date.year())
+            );
+            previous_year = Some(event.date.year());
+        }
+        if event.category.is_empty() {
+            println!("  {}{}", event.description, event.tags_display());
+        } else {
+            println!(
+                "  {} ({}){}",
+                event.description,
+                registry.display(&event.category),
+                event.tags_display()
+            );
+        }
+    }
+    matching.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::date;
+
+    #[test]
+    fn parses_the_rules() {
+        assert_eq!(LeapDay::parse("feb28"), Some(LeapDay::Feb28));
+        assert_eq!(LeapDay::parse("mar1"), Some(LeapDay::Mar1));
+        assert_eq!(LeapDay::parse("skip"), Some(LeapDay::Skip));
+        assert_eq!(LeapDay::parse("march"), None);
+    }
+
+    #[test]
+    fn moves_february_29_by_the_rule() {
+        let leap_day = date(2020, 2, 29);
+        assert_eq!(
+            LeapDay::Feb28.in_year(leap_day, 2023),
+            Some(date(2023, 2, 28))
+        );
+        assert_eq!(
+            LeapDay::Mar1.in_year(leap_day, 2023),
+            Some(date(2023, 3, 1))
+        );
+        assert_eq!(LeapDay::Skip.in_year(leap_day, 2023), None);
+        for rule in [LeapDay::Feb28, LeapDay::Mar1, LeapDay::Skip] {
+            assert_eq!(rule.in_year(leap_day, 2024), Some(date(2024, 2, 29)));
+            assert_eq!(
+                rule.in_year(date(2020, 5, 10), 2023),
+                Some(date(2023, 5, 10))
+            );
+        }
+    }
+
+    #[test]
+    fn counts_whole_years() {
+        let rule = LeapDay::Feb28;
+        assert_eq!(rule.years_since(date(1985, 12, 31), date(2023, 5, 10)), 37);
+        assert_eq!(rule.years_since(date(2022, 5, 10), date(2023, 5, 10)), 1);
+        assert_eq!(rule.years_since(date(2022, 5, 11), date(2023, 5, 10)), 0);
+    }
+
+    #[test]
+    fn counts_years_from_february_29_by_the_rule() {
+        let leap_day = date(2020, 2, 29);
+        assert_eq!(LeapDay::Feb28.years_since(leap_day, date(2023, 2, 28)), 3);
+        assert_eq!(LeapDay::Mar1.years_since(leap_day, date(2023, 2, 28)), 2);
+        assert_eq!(LeapDay::Mar1.years_since(leap_day, date(2023, 3, 1)), 3);
+        assert_eq!(LeapDay::Skip.years_since(leap_day, date(2023, 2, 28)), 2);
+        assert_eq!(LeapDay::Skip.years_since(leap_day, date(2023, 3, 1)), 3);
+    }
+}
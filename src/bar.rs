@@ -0,0 +1,174 @@
+use chrono::NaiveDate;
+use serde_json::json;
+use std::fs;
+use std::io::Write;
+use std::process;
+use std::thread;
+use std::time::Duration;
+
+use crate::agenda;
+use crate::config;
+use crate::date_expr;
+use crate::date_offset::DateOffset;
+use crate::prompt;
+use crate::{read_events, Event};
+
+/// How often events.csv and the date are checked with --watch
+const WATCH_INTERVAL: Duration = Duration::from_secs(2);
+
+/// The JSON formats of the status bars
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Protocol {
+    /// {"text": ..., "tooltip": ..., "class": ...} per line
+    Waybar,
+    /// Blocks with full_text, with --watch the header and the endless array of status lines
+    I3bar,
+}
+
+impl Protocol {
+    pub fn parse(protocol: &str) -> Option<Self> {
+        match protocol {
+            "waybar" => Some(Protocol::Waybar),
+            "i3bar" => Some(Protocol::I3bar),
+            _ => None,
+        }
+    }
+}
+
+/// today if there are events today, soon if there are events in the next week, otherwise none
+fn class(events: &[Event], today: NaiveDate) -> &'static str {
+    let days: Vec<i64> = events
+        .iter()
+        .filter(|e| e.precision.has_day())
+        .map(|e| (e.date - today).num_days())
+        .filter(|days| *days >= 0)
+        .collect();
+    if days.contains(&0) {
+        "today"
+    } else if days.iter().any(|days| *days <= 7) {
+        "soon"
+    } else {
+        "none"
+    }
+}
+
+/// The status of the events as JSON. The text is the prompt template,
+/// the tooltip is the agenda of the days until next
+fn status(events: &[Event], today: NaiveDate, next: DateOffset, protocol: Protocol) -> String {
+    let text = prompt::render(&config::get().prompt_template, events, today);
+    let last = next.apply(today).unwrap_or(today);
+    let mut upcoming: Vec<&Event> = events
+        .iter()
+        .filter(|e| e.precision.has_day() && e.date >= today && e.date <= last)
+        .collect();
+    upcoming.sort_by_key(|e| e.date);
+    let tooltip = agenda::agenda_lines(&upcoming, today).join("\n");
+    let class = class(events, today);
+
+    let status = match protocol {
+        Protocol::Waybar => json!({
+            "text": text,
+            "tooltip": tooltip,
+            "class": class,
+            "alt": class,
+        }),
+        Protocol::I3bar => json!({
+            "name": "days",
+            "full_text": text,
+            "urgent": class == "today",
+        }),
+    };
+    status.to_string()
+}
+
+fn read_or_exit() -> Vec<Event> {
+    match read_events(&config::get().data_file) {
+        Ok(events) => events,
+        Err(e) => {
+            eprintln!("Error reading events.csv file: {}", e);
+            process::exit(1);
+        }
+    }
+}
+
+/// The bar command: --protocol waybar|i3bar, --next <offset> for the tooltip and --watch.
+/// With --watch the status is printed again when events.csv or the date changes,
+/// until the bar closes the output
+pub fn run_bar_command(args: &[String]) {
+    let arg_protocol = "--protocol";
+    let arg_next = "--next";
+    let arg_watch = "--watch";
+    let mut protocol = Protocol::Waybar;
+    let mut next = DateOffset::Weeks(1);
+    let mut watch = false;
+
+    let mut i = 0;
+    while i < args.len() {
+        if args[i] == arg_watch {
+            watch = true;
+            i += 1;
+            continue;
+        }
+        if (args[i] == arg_protocol || args[i] == arg_next) && i + 1 < args.len() {
+            let value = &args[i + 1];
+            if args[i] == arg_protocol {
+                protocol = Protocol::parse(value).unwrap_or_else(|| {
+                    eprintln!("Bad protocol given: {}, use waybar or i3bar", value);
+                    process::exit(1);
+                });
+            } else {
+                next = DateOffset::parse(value).unwrap_or_else(|| {
+                    eprintln!("Bad offset given: {}", value);
+                    process::exit(1);
+                });
+            }
+            i += 2;
+            continue;
+        }
+        eprintln!("Unknown option: {}", args[i]);
+        process::exit(1);
+    }
+
+    if !watch {
+        let status = status(&read_or_exit(), date_expr::current_date(), next, protocol);
+        match protocol {
+            Protocol::Waybar => println!("{}", status),
+            Protocol::I3bar => println!("[{}]", status),
+        }
+        return;
+    }
+
+    // The bar closing the pipe ends the loop
+    let mut stdout = std::io::stdout();
+    let mut write = |line: String| {
+        if writeln!(stdout, "{}", line)
+            .and_then(|_| stdout.flush())
+            .is_err()
+        {
+            process::exit(0);
+        }
+    };
+    if protocol == Protocol::I3bar {
+        write(json!({"version": 1}).to_string());
+        write("[".to_string());
+    }
+
+    let mut previous = None;
+    loop {
+        let today = date_expr::current_date();
+        let modified = fs::metadata(&config::get().data_file)
+            .and_then(|metadata| metadata.modified())
+            .ok();
+        if previous != Some((today, modified)) {
+            let status = status(&read_or_exit(), today, next, protocol);
+            match protocol {
+                Protocol::Waybar => write(status),
+                // The status lines are an endless array, separated with commas
+                Protocol::I3bar if previous.is_none() => write(format!("[{}]", status)),
+                Protocol::I3bar => write(format!(",[{}]", status)),
+            }
+            previous = Some((today, modified));
+        }
+        thread::sleep(WATCH_INTERVAL);
+    }
+}
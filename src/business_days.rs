@@ -0,0 +1,93 @@
+use chrono::{Datelike, NaiveDate};
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+
+use crate::categories;
+use crate::config;
+use crate::holidays;
+use crate::{read_events, Event};
+
+/// Whether the deltas are counted in working days, decided once in main with enable
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Days of the events in non-working categories, from set_days_off or events.csv
+static DAYS_OFF: OnceLock<BTreeSet<NaiveDate>> = OnceLock::new();
+
+/// The holidays of the config by year, computed when a year is first needed
+static HOLIDAYS: Mutex<BTreeMap<i32, BTreeSet<NaiveDate>>> = Mutex::new(BTreeMap::new());
+
+/// Count the deltas in working days
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+/// Use the days off of the events that are already read, so events.csv isn't read again.
+/// Without this they're read from events.csv when a working day is first checked
+pub fn set_days_off(events: &[Event]) {
+    DAYS_OFF.get_or_init(|| days_off(events));
+}
+
+/// The days of the events in non-working categories
+fn days_off(events: &[Event]) -> BTreeSet<NaiveDate> {
+    let registry = categories::get();
+    events
+        .iter()
+        .filter(|e| e.precision.has_day() && !registry.is_working(&e.category))
+        .map(|e| e.date)
+        .collect()
+}
+
+pub fn enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Not on the weekend or a holiday of the config, and not the day of an event in
+/// a non-working category. Every count of working days, like +3wd, the diff command
+/// and --business-days, goes through this
+pub fn is_working_day(date: NaiveDate) -> bool {
+    if config::get().weekend.contains(&date.weekday()) {
+        return false;
+    }
+    let day_off = DAYS_OFF
+        .get_or_init(|| {
+            read_events(&config::get().data_file)
+                .map(|events| days_off(&events))
+                .unwrap_or_default()
+        })
+        .contains(&date);
+    !day_off && !is_holiday(date)
+}
+
+fn is_holiday(date: NaiveDate) -> bool {
+    if config::get().holidays.is_empty() {
+        return false;
+    }
+    let mut holidays = HOLIDAYS.lock().unwrap_or_else(|e| e.into_inner());
+    holidays
+        .entry(date.year())
+        .or_insert_with(|| {
+            holidays::holiday_events(date.year(), date.year())
+                .iter()
+                .map(|e| e.date)
+                .collect()
+        })
+        .contains(&date)
+}
+
+/// Working days after today up to the date, negative for past dates.
+/// So today +Nwd is the date
+pub fn working_days_until(today: NaiveDate, date: NaiveDate) -> i64 {
+    let (start, end, sign) = if date < today {
+        (date, today, -1)
+    } else {
+        (today, date, 1)
+    };
+    let count = start
+        .iter_days()
+        .skip(1)
+        .take_while(|day| *day <= end)
+        .filter(|day| is_working_day(*day))
+        .count() as i64;
+    sign * count
+}
@@ -0,0 +1,161 @@
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use std::collections::BTreeMap;
+
+use crate::categories;
+use crate::colors::{BOLD, INVERSE, RESET};
+use crate::config;
+use crate::Event;
+
+/// Width of one month in characters, seven days of three characters
+const MONTH_WIDTH: usize = 21;
+
+/// Print a cal(1) style grid of a month. Days with events are marked with a *,
+/// on terminals they are also colored by category and today is highlighted
+pub fn print_month(year: i32, month: u32, events: &[&Event], today: NaiveDate, color: bool) {
+    let events_by_day = group_by_day(events);
+    // The padding lines are only needed when months are side by side
+    let lines = month_lines(year, month, &events_by_day, today, color, true);
+    for line in lines.iter().filter(|line| !line.trim().is_empty()) {
+        println!("{}", line.trim_end());
+    }
+}
+
+/// Print the grids of a whole year, three months side by side
+pub fn print_year(year: i32, events: &[&Event], today: NaiveDate, color: bool) {
+    let events_by_day = group_by_day(events);
+    println!("{:^width$}", year, width = MONTH_WIDTH * 3 + 4);
+
+    for first_month in [1, 4, 7, 10] {
+        println!();
+        let months: Vec<Vec<String>> = (first_month..first_month + 3)
+            .map(|month| month_lines(year, month, &events_by_day, today, color, false))
+            .collect();
+        for row in 0..months[0].len() {
+            let line: Vec<&str> = months.iter().map(|lines| lines[row].as_str()).collect();
+            println!("{}", line.join("  "));
+        }
+    }
+}
+
+fn group_by_day<'a>(events: &[&'a Event]) -> BTreeMap<NaiveDate, Vec<&'a Event>> {
+    let mut events_by_day: BTreeMap<NaiveDate, Vec<&Event>> = BTreeMap::new();
+    // Months and years have no day on the calendar
+    for event in events.iter().filter(|e| e.precision.has_day()) {
+        events_by_day.entry(event.date).or_default().push(event);
+    }
+    events_by_day
+}
+
+/// The lines of one month, always eight lines of MONTH_WIDTH visible characters
+/// so the months can be put side by side
+fn month_lines(
+    year: i32,
+    month: u32,
+    events_by_day: &BTreeMap<NaiveDate, Vec<&Event>>,
+    today: NaiveDate,
+    color: bool,
+    with_year: bool,
+) -> Vec<String> {
+    let config = config::get();
+    let mut lines = Vec::new();
+    let name = config.locale.month_name(month);
+    let title = if with_year {
+        format!("{} {}", name, year)
+    } else {
+        name.to_string()
+    };
+    lines.push(format!("{:^width$}", title, width = MONTH_WIDTH));
+    // Weekday names from the week_start of the config
+    let weekdays: Vec<&str> = (0..7)
+        .map(|i| {
+            config
+                .locale
+                .weekday_short(weekday_after(config.week_start, i))
+        })
+        .collect();
+    lines.push(format!(
+        "{:<width$}",
+        weekdays.join(" "),
+        width = MONTH_WIDTH
+    ));
+
+    let first = match NaiveDate::from_ymd_opt(year, month, 1) {
+        Some(first) => first,
+        None => return lines,
+    };
+
+    // Empty cells before the first day
+    let empty_days =
+        (first.weekday().num_days_from_monday() + 7 - config.week_start.num_days_from_monday()) % 7;
+    let mut line = "   ".repeat(empty_days as usize);
+    let mut day = first;
+    while day.month() == month {
+        line.push_str(&day_cell(day, events_by_day.get(&day), today, color));
+        if visible_width(&line) >= MONTH_WIDTH {
+            lines.push(line);
+            line = String::new();
+        }
+        day += Duration::days(1);
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+
+    // Pad the short lines and the missing weeks
+    while lines.len() < 8 {
+        lines.push(String::new());
+    }
+    lines
+        .into_iter()
+        .map(|line| {
+            let padding = MONTH_WIDTH.saturating_sub(visible_width(&line));
+            line + &" ".repeat(padding)
+        })
+        .collect()
+}
+
+/// One day of the grid, the day number and * if the day has events
+fn day_cell(day: NaiveDate, events: Option<&Vec<&Event>>, today: NaiveDate, color: bool) -> String {
+    let marker = if events.is_some() { "*" } else { " " };
+    let number = format!("{:>2}", day.day());
+
+    if !color {
+        return number + marker;
+    }
+
+    // Color by the category of the first event of the day, events without a category are bold
+    let mut styled = match events.and_then(|events| events.first()) {
+        Some(event) if event.category.is_empty() => format!("{}{}{}", BOLD, number, RESET),
+        Some(event) => format!(
+            "{}{}{}",
+            categories::get().color(&event.category),
+            number,
+            RESET
+        ),
+        None => number,
+    };
+    if day == today {
+        styled = format!("{}{}{}", INVERSE, styled, RESET);
+    }
+    styled + marker
+}
+
+fn weekday_after(weekday: Weekday, days: u32) -> Weekday {
+    (0..days).fold(weekday, |weekday, _| weekday.succ())
+}
+
+/// Width of the line without the ANSI color codes
+fn visible_width(line: &str) -> usize {
+    let mut width = 0;
+    let mut in_escape = false;
+    for c in line.chars() {
+        if in_escape {
+            in_escape = c != 'm';
+        } else if c == '\x1b' {
+            in_escape = true;
+        } else {
+            width += 1;
+        }
+    }
+    width
+}
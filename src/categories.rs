@@ -0,0 +1,212 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::process;
+use std::sync::OnceLock;
+
+use crate::colors;
+use crate::config;
+use crate::Event;
+
+static REGISTRY: OnceLock<Registry> = OnceLock::new();
+
+/// A category from categories.csv
+#[derive(Debug)]
+pub struct Category {
+    /// Like computing or work/release, / separates the parent and the child
+    pub name: String,
+    /// Other names for the category, like comp
+    pub aliases: Vec<String>,
+    /// ANSI code of the display color, None uses the default color
+    pub color: Option<String>,
+    pub icon: String,
+    /// False for days off like vacation, the days of its events aren't working days
+    pub working: bool,
+}
+
+/// The known categories, read from categories.csv next to events.csv:
+///
+/// name,aliases,color,icon,working
+/// computing,comp;Computing,blue,💻
+/// work/release,release,bold green,🚀
+/// vacation,,cyan,🌴,no
+///
+/// Aliases are separated with semicolons. Names and aliases are matched ignoring case.
+/// working is no for the categories of days off
+#[derive(Debug)]
+pub struct Registry {
+    categories: Vec<Category>,
+}
+
+/// The registry, read when it's first needed. Without categories.csv it's empty
+pub fn get() -> &'static Registry {
+    REGISTRY.get_or_init(|| Registry::read(&categories_path()))
+}
+
+/// categories_file from the config, or categories.csv next to events.csv
+fn categories_path() -> PathBuf {
+    let config = config::get();
+    match &config.categories_file {
+        Some(path) => path.clone(),
+        None => config
+            .data_file
+            .parent()
+            .map(|directory| directory.join("categories.csv"))
+            .unwrap_or_else(|| PathBuf::from("categories.csv")),
+    }
+}
+
+impl Registry {
+    fn read(path: &PathBuf) -> Self {
+        let mut categories = Vec::new();
+        let csv = match std::fs::read_to_string(path) {
+            Ok(csv) => csv,
+            Err(_) => return Self { categories },
+        };
+
+        let mut rdr = csv::ReaderBuilder::new()
+            .has_headers(true)
+            .flexible(true)
+            .from_reader(csv.as_bytes());
+
+        for result in rdr.records() {
+            let record = match result {
+                Ok(record) => record,
+                Err(e) => {
+                    eprintln!("Error reading {}: {}", path.display(), e);
+                    process::exit(1);
+                }
+            };
+            let field = |i: usize| record.get(i).unwrap_or("").trim().to_string();
+
+            let name = field(0);
+            if name.is_empty() {
+                continue;
+            }
+            let color = match field(2).as_str() {
+                "" => None,
+                style => match colors::parse_style(style) {
+                    Some(color) => Some(color),
+                    None => {
+                        eprintln!("Bad color for {} in {}: {}", name, path.display(), style);
+                        process::exit(1);
+                    }
+                },
+            };
+
+            categories.push(Category {
+                name,
+                aliases: field(1)
+                    .split(';')
+                    .map(|alias| alias.trim().to_string())
+                    .filter(|alias| !alias.is_empty())
+                    .collect(),
+                color,
+                icon: field(3),
+                working: !matches!(field(4).to_lowercase().as_str(), "no" | "false"),
+            });
+        }
+
+        Self { categories }
+    }
+
+    /// The registered category with the name or alias
+    pub fn find(&self, category: &str) -> Option<&Category> {
+        let category = category.trim();
+        self.categories.iter().find(|c| {
+            c.name.eq_ignore_ascii_case(category)
+                || c.aliases.iter().any(|a| a.eq_ignore_ascii_case(category))
+        })
+    }
+
+    /// The registered name of the category, or the category in lowercase if it's not registered
+    pub fn canonical(&self, category: &str) -> String {
+        match self.find(category) {
+            Some(c) => c.name.clone(),
+            None => category.trim().to_lowercase(),
+        }
+    }
+
+    /// Whether the categories are the same, after resolving aliases and case
+    pub fn same(&self, category: &str, other: &str) -> bool {
+        self.canonical(category)
+            .eq_ignore_ascii_case(&self.canonical(other))
+    }
+
+    /// Whether the event's category is the filter or one of its children,
+    /// so work matches work and work/release
+    pub fn matches(&self, category: &str, filter: &str) -> bool {
+        let category = self.canonical(category).to_lowercase();
+        let filter = self.canonical(filter).to_lowercase();
+        category == filter || category.starts_with(&(filter + "/"))
+    }
+
+    /// The color of the category, from the category or its parents, or a color picked by the name
+    pub fn color(&self, category: &str) -> String {
+        let mut name = self.canonical(category);
+        loop {
+            if let Some(color) = self.find(&name).and_then(|c| c.color.clone()) {
+                return color;
+            }
+            match name.rfind('/') {
+                Some(parent) => name.truncate(parent),
+                None => break,
+            }
+        }
+        colors::category_color(&self.canonical(category)).to_string()
+    }
+
+    /// Whether the days of the category's events are working days, false if the category
+    /// or one of its parents is marked non-working. Events without a category are on working days
+    pub fn is_working(&self, category: &str) -> bool {
+        let mut name = self.canonical(category);
+        loop {
+            if self.find(&name).is_some_and(|c| !c.working) {
+                return false;
+            }
+            match name.rfind('/') {
+                Some(parent) => name.truncate(parent),
+                None => return true,
+            }
+        }
+    }
+
+    /// The category with its icon if it has one, like 🚀 work/release
+    pub fn display(&self, category: &str) -> String {
+        match self.find(category) {
+            Some(c) if !c.icon.is_empty() => format!("{} {}", c.icon, category),
+            _ => category.to_string(),
+        }
+    }
+
+    /// Print the registered categories and the categories of the events,
+    /// with the number of events in each. The counts include the children
+    pub fn print_categories(&self, events: &[Event]) {
+        // Registered names and the names used in events, by lowercase name
+        let mut names: BTreeMap<String, String> = BTreeMap::new();
+        for category in self.categories.iter() {
+            names.insert(category.name.to_lowercase(), category.name.clone());
+        }
+        for event in events.iter().filter(|e| !e.category.is_empty()) {
+            let name = self.canonical(&event.category);
+            names.entry(name.to_lowercase()).or_insert(name);
+        }
+
+        println!("{:<30}{:>8}  Aliases", "Category", "Events");
+        for name in names.values() {
+            let count = events
+                .iter()
+                .filter(|e| !e.category.is_empty() && self.matches(&e.category, name))
+                .count();
+            let (display, aliases) = match self.find(name) {
+                Some(c) => (self.display(&c.name), c.aliases.join(", ")),
+                None => (format!("{} (not registered)", name), String::new()),
+            };
+            println!("{:<30}{:>8}  {}", display, count, aliases);
+        }
+
+        let uncategorized = events.iter().filter(|e| e.category.is_empty()).count();
+        if uncategorized > 0 {
+            println!("{:<30}{:>8}", "(no category)", uncategorized);
+        }
+    }
+}
@@ -0,0 +1,162 @@
+use std::env;
+use std::io::IsTerminal;
+use std::process;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::config;
+
+pub const RESET: &str = "\x1b[0m";
+pub const BOLD: &str = "\x1b[1m";
+pub const INVERSE: &str = "\x1b[7m";
+
+/// Colors for the categories: red, green, yellow, blue, magenta and cyan
+const CATEGORY_COLORS: [&str; 6] = [
+    "\x1b[31m", "\x1b[32m", "\x1b[33m", "\x1b[34m", "\x1b[35m", "\x1b[36m",
+];
+
+/// Events this many days ahead are highlighted
+const SOON_DAYS: i64 = 7;
+
+/// Whether colors are used, decided once in main with init
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ColorMode {
+    /// Colors when stdout is a terminal and NO_COLOR is not set
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorMode {
+    pub fn parse(mode: &str) -> Option<Self> {
+        match mode {
+            "auto" => Some(ColorMode::Auto),
+            "always" => Some(ColorMode::Always),
+            "never" => Some(ColorMode::Never),
+            _ => None,
+        }
+    }
+}
+
+/// Take --color <mode> or --color=<mode> out of the arguments, so the commands don't see it.
+/// Returns None if it's not given
+pub fn take_color_option(args: &mut Vec<String>) -> Option<ColorMode> {
+    let arg_color = "--color";
+    let mut mode = None;
+
+    let mut i = 1;
+    while i < args.len() {
+        let value = if args[i] == arg_color {
+            if i + 1 >= args.len() {
+                eprintln!("No value given for --color, use auto, always or never");
+                process::exit(1);
+            }
+            let value = args.remove(i + 1);
+            args.remove(i);
+            value
+        } else if let Some(value) = args[i].strip_prefix("--color=") {
+            let value = value.to_string();
+            args.remove(i);
+            value
+        } else {
+            i += 1;
+            continue;
+        };
+
+        mode = match ColorMode::parse(&value) {
+            Some(mode) => Some(mode),
+            None => {
+                eprintln!("Bad color given: {}, use auto, always or never", value);
+                process::exit(1);
+            }
+        };
+    }
+    mode
+}
+
+/// Decide whether colors are used. NO_COLOR (https://no-color.org) turns them off in auto mode
+pub fn init(mode: ColorMode) {
+    let enabled = match mode {
+        ColorMode::Always => true,
+        ColorMode::Never => false,
+        ColorMode::Auto => {
+            let no_color = env::var("NO_COLOR").is_ok_and(|value| !value.is_empty());
+            !no_color && std::io::stdout().is_terminal()
+        }
+    };
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+pub fn enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// The same category always gets the same color
+pub fn category_color(category: &str) -> &'static str {
+    let hash = category.bytes().fold(0usize, |hash, b| {
+        hash.wrapping_mul(31).wrapping_add(b as usize)
+    });
+    CATEGORY_COLORS[hash % CATEGORY_COLORS.len()]
+}
+
+/// Past events are dimmed, today's are bold and the next week's are highlighted,
+/// the styles can be changed in the config
+pub fn delta_style(delta: i64) -> &'static str {
+    let config = config::get();
+    if delta < 0 {
+        &config.color_past
+    } else if delta == 0 {
+        &config.color_today
+    } else if delta <= SOON_DAYS {
+        &config.color_soon
+    } else {
+        ""
+    }
+}
+
+/// Style of the text matching a search
+pub fn highlight_style() -> &'static str {
+    &config::get().color_highlight
+}
+
+/// Turn a style like "bold green" into an ANSI code, None if a word is unknown.
+/// "none" or an empty style is no style
+pub fn parse_style(style: &str) -> Option<String> {
+    let mut codes = Vec::new();
+    for word in style.split_whitespace() {
+        let code = match word.to_lowercase().as_str() {
+            "none" => continue,
+            "bold" => "1",
+            "dim" => "2",
+            "italic" => "3",
+            "underline" => "4",
+            "inverse" => "7",
+            "black" => "30",
+            "red" => "31",
+            "green" => "32",
+            "yellow" => "33",
+            "blue" => "34",
+            "magenta" => "35",
+            "cyan" => "36",
+            "white" => "37",
+            _ => return None,
+        };
+        codes.push(code);
+    }
+
+    if codes.is_empty() {
+        Some(String::new())
+    } else {
+        Some(format!("\x1b[{}m", codes.join(";")))
+    }
+}
+
+/// Wrap the text in the style, if colors are used
+pub fn paint(text: &str, style: &str) -> String {
+    if enabled() && !style.is_empty() {
+        format!("{}{}{}", style, text, RESET)
+    } else {
+        text.to_string()
+    }
+}
@@ -0,0 +1,444 @@
+use chrono::format::{Item, StrftimeItems};
+use chrono::Weekday;
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::process;
+use std::sync::OnceLock;
+
+use crate::anniversary::LeapDay;
+use crate::colors::{self, ColorMode};
+use crate::holidays;
+use crate::home_directory;
+use crate::hooks;
+use crate::locale::Locale;
+
+/// The keys of the config file with their default values and descriptions
+const KEYS: [(&str, &str, &str); 26] = [
+    (
+        "data_file",
+        "~/.days/events.csv",
+        "Location of the events file",
+    ),
+    (
+        "categories_file",
+        "",
+        "Location of categories.csv, by default next to the events file",
+    ),
+    (
+        "reminders_file",
+        "",
+        "Where the fired reminders are kept, by default reminders.csv next to the events file",
+    ),
+    (
+        "default_command",
+        "",
+        "Command to run without arguments, like 'agenda --next 14d'",
+    ),
+    (
+        "template",
+        "{date}: {description} ({category}){tags} - {delta}",
+        "Format of the printed events",
+    ),
+    (
+        "prompt_template",
+        "today: {today} / next: {next} in {next_days}d",
+        "Format of the prompt command",
+    ),
+    (
+        "date_format",
+        "%Y-%m-%d",
+        "Format of the printed dates, like %d.%m.%Y",
+    ),
+    ("locale", "en", "Language of the printed texts, en or fi"),
+    (
+        "timezone",
+        "utc",
+        "Timezone of today, utc, local or an offset like +03:00",
+    ),
+    ("color", "auto", "Colors, auto, always or never"),
+    ("color_today", "bold green", "Style of today's events"),
+    (
+        "color_soon",
+        "bold yellow",
+        "Style of the next week's events",
+    ),
+    ("color_past", "dim", "Style of past events"),
+    (
+        "color_highlight",
+        "inverse",
+        "Style of the text matching --search",
+    ),
+    ("week_start", "monday", "First day of the week"),
+    (
+        "weekend",
+        "sat,sun",
+        "Days that aren't working days, for --business-days",
+    ),
+    (
+        "holidays",
+        "",
+        "Holidays to show with the events, like fi for Finland",
+    ),
+    (
+        "confirm_delete",
+        "false",
+        "Ask before deleting each event, true or false",
+    ),
+    (
+        "anniversaries",
+        "false",
+        "Show events over a year ago with their next anniversary, true or false",
+    ),
+    (
+        "leap_day",
+        "feb28",
+        "Day of February 29 events in other years, feb28, mar1 or skip",
+    ),
+    (
+        "pre_add",
+        "",
+        "Command to run before adding, a failing command cancels it",
+    ),
+    ("post_add", "", "Command to run after adding"),
+    (
+        "pre_edit",
+        "",
+        "Command to run before shifting, a failing command cancels it",
+    ),
+    ("post_edit", "", "Command to run after shifting"),
+    (
+        "pre_delete",
+        "",
+        "Command to run before deleting, a failing command cancels it",
+    ),
+    ("post_delete", "", "Command to run after deleting"),
+];
+
+/// The keys that are written as true or false instead of strings
+const BOOLEAN_KEYS: [&str; 2] = ["confirm_delete", "anniversaries"];
+
+static CONFIG: OnceLock<Config> = OnceLock::new();
+
+/// Settings from config.toml, the defaults are used for the missing keys
+#[derive(Debug)]
+pub struct Config {
+    /// Where the config was read from, None if there's no config file
+    pub path: Option<PathBuf>,
+    table: toml::Table,
+    pub data_file: PathBuf,
+    /// None means categories.csv next to the events file
+    pub categories_file: Option<PathBuf>,
+    /// None means reminders.csv next to the events file
+    pub reminders_file: Option<PathBuf>,
+    pub default_command: Vec<String>,
+    pub template: String,
+    pub prompt_template: String,
+    pub date_format: String,
+    pub locale: Locale,
+    pub timezone: String,
+    pub color: ColorMode,
+    pub color_today: String,
+    pub color_soon: String,
+    pub color_past: String,
+    pub color_highlight: String,
+    pub week_start: Weekday,
+    /// Days that aren't working days
+    pub weekend: Vec<Weekday>,
+    /// The names of the holiday providers, like fi
+    pub holidays: Vec<String>,
+    pub confirm_delete: bool,
+    pub anniversaries: bool,
+    pub leap_day: LeapDay,
+    /// The commands of the hooks that are set, like pre_add
+    pub hooks: BTreeMap<String, String>,
+}
+
+/// The config, read when it's first needed
+pub fn get() -> &'static Config {
+    CONFIG.get_or_init(|| {
+        let path = find_config_file();
+        let table = match &path {
+            Some(path) => read_table(path),
+            None => toml::Table::new(),
+        };
+        Config::from_table(path, table)
+    })
+}
+
+impl Config {
+    /// Check the values and fill in the defaults, exit with an error message on bad values
+    fn from_table(path: Option<PathBuf>, table: toml::Table) -> Self {
+        for key in table.keys() {
+            if !KEYS.iter().any(|(name, _, _)| name == key) {
+                eprintln!("Unknown key in config: {}", key);
+            }
+        }
+
+        let value = |key: &str| -> String {
+            match table.get(key) {
+                Some(toml::Value::String(value)) => value.clone(),
+                Some(value) => value.to_string(),
+                None => default_value(key).to_string(),
+            }
+        };
+        let bad_value = |key: &str| -> ! {
+            eprintln!("Bad value for {} in config: {}", key, value(key));
+            process::exit(1);
+        };
+
+        let locale = Locale::parse(&value("locale")).unwrap_or_else(|| bad_value("locale"));
+        let color = ColorMode::parse(&value("color")).unwrap_or_else(|| bad_value("color"));
+        let week_start = value("week_start")
+            .parse::<Weekday>()
+            .unwrap_or_else(|_| bad_value("week_start"));
+        let confirm_delete = match value("confirm_delete").as_str() {
+            "true" => true,
+            "false" => false,
+            _ => bad_value("confirm_delete"),
+        };
+        let leap_day = LeapDay::parse(&value("leap_day")).unwrap_or_else(|| bad_value("leap_day"));
+        let anniversaries = match value("anniversaries").as_str() {
+            "true" => true,
+            "false" => false,
+            _ => bad_value("anniversaries"),
+        };
+
+        let weekend: Vec<Weekday> = value("weekend")
+            .split(',')
+            .map(|day| day.trim())
+            .filter(|day| !day.is_empty())
+            .map(|day| {
+                day.parse::<Weekday>()
+                    .unwrap_or_else(|_| bad_value("weekend"))
+            })
+            .collect();
+
+        let holidays: Vec<String> = value("holidays")
+            .split(',')
+            .map(|name| name.trim().to_string())
+            .filter(|name| !name.is_empty())
+            .collect();
+        if holidays
+            .iter()
+            .any(|name| holidays::provider(name).is_none())
+        {
+            bad_value("holidays");
+        }
+
+        // A bad date format would panic when printing
+        let date_format = value("date_format");
+        if StrftimeItems::new(&date_format).any(|item| item == Item::Error) {
+            bad_value("date_format");
+        }
+
+        let timezone = value("timezone");
+        if timezone != "utc" && timezone != "local" && parse_offset(&timezone).is_none() {
+            bad_value("timezone");
+        }
+
+        let style = |key: &str| colors::parse_style(&value(key)).unwrap_or_else(|| bad_value(key));
+
+        Self {
+            path,
+            data_file: expand_home(&value("data_file")),
+            categories_file: match value("categories_file").as_str() {
+                "" => None,
+                path => Some(expand_home(path)),
+            },
+            reminders_file: match value("reminders_file").as_str() {
+                "" => None,
+                path => Some(expand_home(path)),
+            },
+            default_command: value("default_command")
+                .split_whitespace()
+                .map(|s| s.to_string())
+                .collect(),
+            template: value("template"),
+            prompt_template: value("prompt_template"),
+            date_format,
+            locale,
+            timezone,
+            color,
+            color_today: style("color_today"),
+            color_soon: style("color_soon"),
+            color_past: style("color_past"),
+            color_highlight: style("color_highlight"),
+            week_start,
+            weekend,
+            holidays,
+            confirm_delete,
+            anniversaries,
+            leap_day,
+            hooks: hooks::HOOKS
+                .iter()
+                .map(|hook| (hook.to_string(), value(hook)))
+                .filter(|(_, command)| !command.trim().is_empty())
+                .collect(),
+            table,
+        }
+    }
+}
+
+fn default_value(key: &str) -> &'static str {
+    match KEYS.iter().find(|(name, _, _)| *name == key) {
+        Some((_, default, _)) => default,
+        None => "",
+    }
+}
+
+/// The timezone offset like +03:00 or -05:00 in seconds
+pub fn parse_offset(offset: &str) -> Option<i32> {
+    let (sign, rest) = match offset.strip_prefix('-') {
+        Some(rest) => (-1, rest),
+        None => (1, offset.strip_prefix('+')?),
+    };
+    let (hours, minutes) = rest.split_once(':').unwrap_or((rest, "0"));
+    let hours: i32 = hours.parse().ok()?;
+    let minutes: i32 = minutes.parse().ok()?;
+    if hours > 23 || minutes > 59 {
+        return None;
+    }
+    Some(sign * (hours * 3600 + minutes * 60))
+}
+
+/// Replace ~ at the start of the path with the home directory
+fn expand_home(path: &str) -> PathBuf {
+    match path.strip_prefix("~/") {
+        Some(rest) => home_directory().join(rest),
+        None => PathBuf::from(path),
+    }
+}
+
+/// The config file is DAYS_CONFIG, ~/.config/days/config.toml or ~/.days/config.toml,
+/// whichever exists first
+fn find_config_file() -> Option<PathBuf> {
+    if let Ok(path) = env::var("DAYS_CONFIG") {
+        return Some(PathBuf::from(path));
+    }
+    [
+        user_config_path(),
+        home_directory().join(".days").join("config.toml"),
+    ]
+    .into_iter()
+    .find(|path| path.exists())
+}
+
+/// ~/.config/days/config.toml, or under XDG_CONFIG_HOME if it's set
+fn user_config_path() -> PathBuf {
+    let config_directory = match env::var("XDG_CONFIG_HOME") {
+        Ok(directory) if !directory.is_empty() => PathBuf::from(directory),
+        _ => home_directory().join(".config"),
+    };
+    config_directory.join("days").join("config.toml")
+}
+
+fn read_table(path: &PathBuf) -> toml::Table {
+    // A missing DAYS_CONFIG file is the same as an empty one
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(_) => return toml::Table::new(),
+    };
+    match content.parse::<toml::Table>() {
+        Ok(table) => table,
+        Err(e) => {
+            eprintln!("Error reading config {}: {}", path.display(), e);
+            process::exit(1);
+        }
+    }
+}
+
+/// Print a value like it's written in the config file
+fn print_value(value: &toml::Value) -> String {
+    match value {
+        toml::Value::String(value) => value.clone(),
+        value => value.to_string(),
+    }
+}
+
+/// The config command: list, get <key> or set <key> <value>
+pub fn run_config_command(args: &[String]) {
+    let arg_list = "list";
+    let arg_get = "get";
+    let arg_set = "set";
+    let config = get();
+
+    if args.is_empty() || args[0] == arg_list {
+        match &config.path {
+            Some(path) => println!("# {}", path.display()),
+            None => println!("# No config file, using the defaults"),
+        }
+        for (key, default, description) in KEYS.iter() {
+            let value = match config.table.get(*key) {
+                Some(value) => print_value(value),
+                None => default.to_string(),
+            };
+            println!("{} = {:?}  # {}", key, value, description);
+        }
+        return;
+    }
+
+    if args[0] == arg_get {
+        if args.len() < 2 {
+            eprintln!("No key given");
+            process::exit(1);
+        }
+        if !KEYS.iter().any(|(name, _, _)| *name == args[1]) {
+            eprintln!("Unknown key: {}", args[1]);
+            process::exit(1);
+        }
+        match config.table.get(&args[1]) {
+            Some(value) => println!("{}", print_value(value)),
+            None => println!("{}", default_value(&args[1])),
+        }
+        return;
+    }
+
+    if args[0] == arg_set {
+        if args.len() < 3 {
+            eprintln!("No key or value given");
+            process::exit(1);
+        }
+        let (key, value) = (&args[1], &args[2]);
+        if !KEYS.iter().any(|(name, _, _)| name == key) {
+            eprintln!("Unknown key: {}", key);
+            process::exit(1);
+        }
+
+        let mut table = config.table.clone();
+        let value = match value.as_str() {
+            "true" if BOOLEAN_KEYS.contains(&key.as_str()) => toml::Value::Boolean(true),
+            "false" if BOOLEAN_KEYS.contains(&key.as_str()) => toml::Value::Boolean(false),
+            _ => toml::Value::String(value.clone()),
+        };
+        table.insert(key.clone(), value);
+
+        // Check the new value before writing it
+        let path = config.path.clone().unwrap_or_else(user_config_path);
+        Config::from_table(Some(path.clone()), table.clone());
+
+        if let Some(directory) = path.parent() {
+            if let Err(e) = fs::create_dir_all(directory) {
+                eprintln!("Error creating {}: {}", directory.display(), e);
+                process::exit(1);
+            }
+        }
+        match fs::write(&path, toml::to_string(&table).unwrap()) {
+            Ok(_) => println!(
+                "Set {} = {:?} in {}",
+                key,
+                print_value(&table[key]),
+                path.display()
+            ),
+            Err(e) => {
+                eprintln!("Error writing config {}: {}", path.display(), e);
+                process::exit(1);
+            }
+        }
+        return;
+    }
+
+    eprintln!("Unknown config command: {}, use list, get or set", args[0]);
+    process::exit(1);
+}
@@ -0,0 +1,134 @@
+use chrono::{Months, NaiveDate};
+use std::process;
+
+use crate::business_days;
+use crate::config;
+use crate::date_expr;
+use crate::date_offset::DateOffset;
+use crate::format_delta;
+
+/// Whole years, months and days from the start to the end, all negative if the end is earlier
+fn years_months_days(start: NaiveDate, end: NaiveDate) -> (i64, i64, i64) {
+    if end < start {
+        let (years, months, days) = years_months_days(end, start);
+        return (-years, -months, -days);
+    }
+    let mut months = 0;
+    while start
+        .checked_add_months(Months::new(months + 1))
+        .is_some_and(|date| date <= end)
+    {
+        months += 1;
+    }
+    let days = match start.checked_add_months(Months::new(months)) {
+        Some(date) => (end - date).num_days(),
+        None => 0,
+    };
+    ((months / 12).into(), (months % 12).into(), days)
+}
+
+/// The date with the weekday and how far it is from today, like Friday 2023-05-12 - in 2 days
+fn describe(date: NaiveDate, today: NaiveDate) -> String {
+    let config = config::get();
+    format!(
+        "{} - {}",
+        config.locale.weekday_and_date(date, &config.date_format),
+        format_delta(&(date - today).num_days())
+    )
+}
+
+/// The diff command: days between two dates as calendar days, working days, weeks and
+/// years, months and days. All the numbers are negative if the second date is earlier
+pub fn run_diff_command(args: &[String], today: NaiveDate) {
+    if args.len() != 2 {
+        eprintln!("Give two dates, like days diff 2023-05-10 2038-01-19");
+        process::exit(1);
+    }
+    let start = date_expr::parse_date_or_exit(&args[0], today);
+    let end = date_expr::parse_date_or_exit(&args[1], today);
+
+    let days = (end - start).num_days();
+    let (years, months, rest_days) = years_months_days(start, end);
+
+    println!("From: {}", describe(start, today));
+    println!("To:   {}", describe(end, today));
+    println!();
+    println!("{:<14}{}", "Days:", days);
+    println!(
+        "{:<14}{}",
+        "Working days:",
+        business_days::working_days_until(start, end)
+    );
+    // Division and remainder keep the sign, so -16 days is -2 weeks -2 days
+    println!("{:<14}{} weeks {} days", "Weeks:", days / 7, days % 7);
+    println!(
+        "{:<14}{} years {} months {} days",
+        "Years:", years, months, rest_days
+    );
+}
+
+/// The calc command: a date moved by offsets, like days calc 2023-05-10 +90d -2wd.
+/// The date can be any date expression, without offsets the date itself is shown
+pub fn run_calc_command(args: &[String], today: NaiveDate) {
+    if args.is_empty() {
+        eprintln!("Give a date and offsets, like days calc 2023-05-10 +90d");
+        process::exit(1);
+    }
+    let mut date = date_expr::parse_date_or_exit(&args[0], today);
+    for offset in args[1..].iter() {
+        let moved = match DateOffset::parse(offset) {
+            Some(parsed) => parsed.apply(date),
+            None => {
+                eprintln!("Bad offset given: {}", offset);
+                process::exit(1);
+            }
+        };
+        date = match moved {
+            Some(moved) => moved,
+            None => {
+                eprintln!("Date out of range when adding {}", offset);
+                process::exit(1);
+            }
+        };
+    }
+    println!("{}", describe(date, today));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::date;
+
+    #[test]
+    fn counts_years_months_and_days() {
+        assert_eq!(
+            years_months_days(date(2023, 5, 10), date(2023, 5, 10)),
+            (0, 0, 0)
+        );
+        assert_eq!(
+            years_months_days(date(2023, 5, 10), date(2038, 1, 19)),
+            (14, 8, 9)
+        );
+        // Months are clamped to their last day before the days are counted
+        assert_eq!(
+            years_months_days(date(2023, 1, 31), date(2023, 3, 1)),
+            (0, 1, 1)
+        );
+        assert_eq!(
+            years_months_days(date(2020, 2, 29), date(2024, 2, 28)),
+            (3, 11, 30)
+        );
+    }
+
+    #[test]
+    fn earlier_end_is_negative() {
+        assert_eq!(
+            years_months_days(date(2023, 5, 12), date(2023, 5, 10)),
+            (0, 0, -2)
+        );
+        assert_eq!(
+            years_months_days(date(2038, 1, 19), date(2023, 5, 10)),
+            (-14, -8, -9)
+        );
+    }
+}
@@ -0,0 +1,238 @@
+use chrono::{Datelike, Duration, FixedOffset, Local, NaiveDate, Utc, Weekday};
+use std::env;
+use std::process;
+
+use crate::config;
+use crate::date_offset::DateOffset;
+
+/// The current date in the timezone of the config. Can be set with the DAYS_TODAY
+/// environment variable, like DAYS_TODAY=2023-05-10, to see the events as they were
+/// or will be on that day
+pub fn current_date() -> NaiveDate {
+    let timezone = &config::get().timezone;
+    let today = if timezone == "local" {
+        Local::now().date_naive()
+    } else {
+        // utc or an offset like +03:00, the config has checked it already
+        let offset = config::parse_offset(timezone).unwrap_or(0);
+        match FixedOffset::east_opt(offset) {
+            Some(offset) => Utc::now().with_timezone(&offset).date_naive(),
+            None => Utc::now().date_naive(),
+        }
+    };
+    match env::var("DAYS_TODAY") {
+        Ok(date) => match parse_date_expression(&date, today) {
+            Some(date) => date,
+            None => {
+                eprintln!("Bad date in DAYS_TODAY: {}", date);
+                process::exit(1);
+            }
+        },
+        Err(_) => today,
+    }
+}
+
+/// Parse a date expression, relative expressions are resolved against today. Accepted are
+/// 2023-05-10, 10.5.2023, 2023-W19-3, 2023-W19, today, tomorrow, yesterday,
+/// offsets like +3d or -2w, weekdays like friday, next friday or last friday,
+/// and start/end of week, month or year
+pub fn parse_date_expression(expression: &str, today: NaiveDate) -> Option<NaiveDate> {
+    let expression = expression.trim().to_lowercase();
+
+    // Fixed dates: ISO, Finnish and ISO week dates
+    if let Ok(date) = NaiveDate::parse_from_str(&expression, "%Y-%m-%d") {
+        return Some(date);
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(&expression, "%d.%m.%Y") {
+        return Some(date);
+    }
+    let iso_week = expression.to_uppercase();
+    if let Ok(date) = NaiveDate::parse_from_str(&iso_week, "%G-W%V-%u") {
+        return Some(date);
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(&(iso_week + "-1"), "%G-W%V-%u") {
+        return Some(date);
+    }
+
+    match expression.as_str() {
+        "today" => return Some(today),
+        "tomorrow" => return today.succ_opt(),
+        "yesterday" => return today.pred_opt(),
+        "start of week" => return Some(start_of_week(today)),
+        "end of week" => return Some(start_of_week(today) + Duration::days(6)),
+        "start of month" => return today.with_day(1),
+        "end of month" => return end_of_month(today),
+        "start of year" => return NaiveDate::from_ymd_opt(today.year(), 1, 1),
+        "end of year" => return NaiveDate::from_ymd_opt(today.year(), 12, 31),
+        _ => {}
+    }
+
+    // Offsets like +3d, -2w or +1m
+    if let Some(offset) = DateOffset::parse(&expression) {
+        return offset.apply(today);
+    }
+
+    // Weekdays: friday is today or the next friday, next and last skip today
+    let words: Vec<&str> = expression.split_whitespace().collect();
+    match words.as_slice() {
+        [weekday] => {
+            let weekday = weekday.parse::<Weekday>().ok()?;
+            Some(today + Duration::days(days_until(today.weekday(), weekday)))
+        }
+        ["next", weekday] => {
+            let weekday = weekday.parse::<Weekday>().ok()?;
+            let days = days_until(today.weekday(), weekday);
+            Some(today + Duration::days(if days == 0 { 7 } else { days }))
+        }
+        ["last", weekday] => {
+            let weekday = weekday.parse::<Weekday>().ok()?;
+            let days = days_until(weekday, today.weekday());
+            Some(today - Duration::days(if days == 0 { 7 } else { days }))
+        }
+        _ => None,
+    }
+}
+
+/// Parse a date expression, exit with an error message if it's not valid
+pub fn parse_date_or_exit(expression: &str, today: NaiveDate) -> NaiveDate {
+    match parse_date_expression(expression, today) {
+        Some(date) => date,
+        None => {
+            eprintln!(
+                "Bad date given: {}, use for example 2023-05-10, 10.5.2023, today, +3d or 'next friday'",
+                expression
+            );
+            process::exit(1);
+        }
+    }
+}
+
+/// The first and last day of an ISO week, like 2023-W23
+pub fn parse_week(week: &str) -> Option<(NaiveDate, NaiveDate)> {
+    let start =
+        NaiveDate::parse_from_str(&(week.trim().to_uppercase() + "-1"), "%G-W%V-%u").ok()?;
+    Some((start, start + Duration::days(6)))
+}
+
+/// The first and last day of a month, like 2023-05
+pub fn parse_month(month: &str) -> Option<(NaiveDate, NaiveDate)> {
+    let start = NaiveDate::parse_from_str(&(month.trim().to_string() + "-01"), "%Y-%m-%d").ok()?;
+    Some((start, end_of_month(start)?))
+}
+
+/// The first and last day of a quarter, like 2023-Q2
+pub fn parse_quarter(quarter: &str) -> Option<(NaiveDate, NaiveDate)> {
+    let quarter = quarter.trim().to_uppercase();
+    let (year, number) = quarter.split_once("-Q")?;
+    let number: u32 = number.parse().ok()?;
+    if !(1..=4).contains(&number) {
+        return None;
+    }
+    let start = NaiveDate::from_ymd_opt(year.parse().ok()?, (number - 1) * 3 + 1, 1)?;
+    let last_month = NaiveDate::from_ymd_opt(start.year(), number * 3, 1)?;
+    Some((start, end_of_month(last_month)?))
+}
+
+/// The first and last day of a year, like 2023
+pub fn parse_year(year: &str) -> Option<(NaiveDate, NaiveDate)> {
+    let year: i32 = year.trim().parse().ok()?;
+    Some((
+        NaiveDate::from_ymd_opt(year, 1, 1)?,
+        NaiveDate::from_ymd_opt(year, 12, 31)?,
+    ))
+}
+
+/// Days from one weekday forward to another, 0 to 6
+fn days_until(from: Weekday, to: Weekday) -> i64 {
+    (to.num_days_from_monday() as i64 - from.num_days_from_monday() as i64).rem_euclid(7)
+}
+
+/// The first day of the week, weeks start on the week_start of the config
+fn start_of_week(date: NaiveDate) -> NaiveDate {
+    date - Duration::days(days_until(config::get().week_start, date.weekday()))
+}
+
+fn end_of_month(date: NaiveDate) -> Option<NaiveDate> {
+    let (year, month) = if date.month() == 12 {
+        (date.year() + 1, 1)
+    } else {
+        (date.year(), date.month() + 1)
+    };
+    NaiveDate::from_ymd_opt(year, month, 1)?.pred_opt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::date;
+
+    /// A Wednesday
+    fn today() -> NaiveDate {
+        date(2023, 5, 10)
+    }
+
+    fn parse(expression: &str) -> Option<NaiveDate> {
+        parse_date_expression(expression, today())
+    }
+
+    #[test]
+    fn parses_fixed_dates() {
+        assert_eq!(parse("2023-06-01"), Some(date(2023, 6, 1)));
+        assert_eq!(parse("1.6.2023"), Some(date(2023, 6, 1)));
+        assert_eq!(parse("2023-W19-3"), Some(date(2023, 5, 10)));
+        assert_eq!(parse("2023-w19"), Some(date(2023, 5, 8)));
+        assert_eq!(parse("2020-W53-5"), Some(date(2021, 1, 1)));
+        assert_eq!(parse("2023-02-30"), None);
+    }
+
+    #[test]
+    fn parses_relative_dates() {
+        assert_eq!(parse("today"), Some(today()));
+        assert_eq!(parse("Tomorrow"), Some(date(2023, 5, 11)));
+        assert_eq!(parse("yesterday"), Some(date(2023, 5, 9)));
+        assert_eq!(parse("+3d"), Some(date(2023, 5, 13)));
+        assert_eq!(parse("-2w"), Some(date(2023, 4, 26)));
+        assert_eq!(parse("+1m"), Some(date(2023, 6, 10)));
+        assert_eq!(parse("end of month"), Some(date(2023, 5, 31)));
+        assert_eq!(parse("start of year"), Some(date(2023, 1, 1)));
+        assert_eq!(parse("end of year"), Some(date(2023, 12, 31)));
+    }
+
+    #[test]
+    fn parses_weekdays() {
+        assert_eq!(parse("friday"), Some(date(2023, 5, 12)));
+        assert_eq!(parse("wednesday"), Some(today()));
+        assert_eq!(parse("next wednesday"), Some(date(2023, 5, 17)));
+        assert_eq!(parse("last wednesday"), Some(date(2023, 5, 3)));
+        assert_eq!(parse("last friday"), Some(date(2023, 5, 5)));
+        assert_eq!(parse("next someday"), None);
+    }
+
+    #[test]
+    fn out_of_range_offsets_are_none() {
+        assert_eq!(parse("+99999999999999d"), None);
+        assert_eq!(parse("+9999999999999w"), None);
+    }
+
+    #[test]
+    fn parses_periods() {
+        assert_eq!(
+            parse_week("2023-W23"),
+            Some((date(2023, 6, 5), date(2023, 6, 11)))
+        );
+        assert_eq!(
+            parse_month("2024-02"),
+            Some((date(2024, 2, 1), date(2024, 2, 29)))
+        );
+        assert_eq!(
+            parse_quarter("2023-q4"),
+            Some((date(2023, 10, 1), date(2023, 12, 31)))
+        );
+        assert_eq!(parse_quarter("2023-Q5"), None);
+        assert_eq!(
+            parse_year("2023"),
+            Some((date(2023, 1, 1), date(2023, 12, 31)))
+        );
+        assert_eq!(parse_month("2023-13"), None);
+    }
+}
@@ -0,0 +1,196 @@
+use chrono::{Days, Months, NaiveDate};
+use std::fmt;
+
+use crate::business_days;
+use crate::config;
+
+/// An offset to move dates by, like +14d, -2w, +1m, +1y or +3wd (working days)
+#[derive(Debug, Clone, Copy)]
+pub enum DateOffset {
+    Days(i64),
+    Weeks(i64),
+    Months(i64),
+    Years(i64),
+    WorkingDays(i64),
+}
+
+impl DateOffset {
+    /// Parse an offset like +14d. The sign is optional, positive is the default
+    pub fn parse(offset: &str) -> Option<Self> {
+        let offset = offset.trim();
+        let (sign, rest) = match offset.strip_prefix('-') {
+            Some(rest) => (-1, rest),
+            None => (1, offset.strip_prefix('+').unwrap_or(offset)),
+        };
+
+        // Split the number and the unit, like 14 and d
+        let unit_start = rest.find(|c: char| !c.is_ascii_digit())?;
+        let (number, unit) = rest.split_at(unit_start);
+        let amount = sign * number.parse::<i64>().ok()?;
+
+        match unit {
+            "d" | "day" | "days" => Some(DateOffset::Days(amount)),
+            "w" | "week" | "weeks" => Some(DateOffset::Weeks(amount)),
+            "m" | "month" | "months" => Some(DateOffset::Months(amount)),
+            "y" | "year" | "years" => Some(DateOffset::Years(amount)),
+            "wd" | "workday" | "workdays" => Some(DateOffset::WorkingDays(amount)),
+            _ => None,
+        }
+    }
+
+    /// The same offset in the other direction
+    pub fn negated(&self) -> Self {
+        match *self {
+            DateOffset::Days(days) => DateOffset::Days(-days),
+            DateOffset::Weeks(weeks) => DateOffset::Weeks(-weeks),
+            DateOffset::Months(months) => DateOffset::Months(-months),
+            DateOffset::Years(years) => DateOffset::Years(-years),
+            DateOffset::WorkingDays(days) => DateOffset::WorkingDays(-days),
+        }
+    }
+
+    /// Move the date by the offset, returns None if the result is out of range.
+    /// Months and years are clamped to the last day of the month, so 2023-01-31 +1m is 2023-02-28
+    pub fn apply(&self, date: NaiveDate) -> Option<NaiveDate> {
+        match *self {
+            DateOffset::Days(days) => add_days(date, days),
+            DateOffset::Weeks(weeks) => add_days(date, weeks.checked_mul(7)?),
+            DateOffset::Months(months) => add_months(date, months),
+            DateOffset::Years(years) => add_months(date, years.checked_mul(12)?),
+            DateOffset::WorkingDays(days) => add_working_days(date, days),
+        }
+    }
+}
+
+/// Like +14d, the way the offsets are given
+impl fmt::Display for DateOffset {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (amount, unit) = match *self {
+            DateOffset::Days(days) => (days, "d"),
+            DateOffset::Weeks(weeks) => (weeks, "w"),
+            DateOffset::Months(months) => (months, "m"),
+            DateOffset::Years(years) => (years, "y"),
+            DateOffset::WorkingDays(days) => (days, "wd"),
+        };
+        write!(f, "{:+}{}", amount, unit)
+    }
+}
+
+fn add_days(date: NaiveDate, days: i64) -> Option<NaiveDate> {
+    let count = Days::new(days.unsigned_abs());
+    if days < 0 {
+        date.checked_sub_days(count)
+    } else {
+        date.checked_add_days(count)
+    }
+}
+
+fn add_months(date: NaiveDate, months: i64) -> Option<NaiveDate> {
+    let count = Months::new(u32::try_from(months.unsigned_abs()).ok()?);
+    if months < 0 {
+        date.checked_sub_months(count)
+    } else {
+        date.checked_add_months(count)
+    }
+}
+
+/// Move the date by working days, skipping the weekend and the holidays of the config
+fn add_working_days(date: NaiveDate, days: i64) -> Option<NaiveDate> {
+    // Every day off would never get anywhere
+    if config::get().weekend.len() == 7 {
+        return None;
+    }
+    let mut date = date;
+    let mut remaining = days.unsigned_abs();
+
+    while remaining > 0 {
+        date = add_days(date, days.signum())?;
+        if business_days::is_working_day(date) {
+            remaining -= 1;
+        }
+    }
+
+    Some(date)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::date;
+
+    #[test]
+    fn parses_offsets() {
+        assert!(matches!(
+            DateOffset::parse("+14d"),
+            Some(DateOffset::Days(14))
+        ));
+        assert!(matches!(
+            DateOffset::parse("-2w"),
+            Some(DateOffset::Weeks(-2))
+        ));
+        assert!(matches!(
+            DateOffset::parse("1m"),
+            Some(DateOffset::Months(1))
+        ));
+        assert!(matches!(
+            DateOffset::parse("+1years"),
+            Some(DateOffset::Years(1))
+        ));
+        assert!(matches!(
+            DateOffset::parse("-3wd"),
+            Some(DateOffset::WorkingDays(-3))
+        ));
+        assert!(DateOffset::parse("+3x").is_none());
+        assert!(DateOffset::parse("d").is_none());
+        assert!(DateOffset::parse("14").is_none());
+    }
+
+    #[test]
+    fn prints_offsets_like_they_are_given() {
+        assert_eq!(DateOffset::Days(14).to_string(), "+14d");
+        assert_eq!(DateOffset::WorkingDays(-3).to_string(), "-3wd");
+    }
+
+    #[test]
+    fn moves_by_days_and_weeks() {
+        assert_eq!(
+            DateOffset::Days(3).apply(date(2023, 5, 30)),
+            Some(date(2023, 6, 2))
+        );
+        assert_eq!(
+            DateOffset::Weeks(-2).apply(date(2023, 1, 5)),
+            Some(date(2022, 12, 22))
+        );
+    }
+
+    #[test]
+    fn clamps_months_to_the_last_day() {
+        assert_eq!(
+            DateOffset::Months(1).apply(date(2023, 1, 31)),
+            Some(date(2023, 2, 28))
+        );
+        assert_eq!(
+            DateOffset::Months(1).apply(date(2024, 1, 31)),
+            Some(date(2024, 2, 29))
+        );
+        assert_eq!(
+            DateOffset::Months(-1).apply(date(2023, 3, 31)),
+            Some(date(2023, 2, 28))
+        );
+        assert_eq!(
+            DateOffset::Years(1).apply(date(2024, 2, 29)),
+            Some(date(2025, 2, 28))
+        );
+    }
+
+    #[test]
+    fn out_of_range_is_none() {
+        let today = date(2023, 5, 10);
+        assert_eq!(DateOffset::Days(99_999_999_999_999).apply(today), None);
+        assert_eq!(DateOffset::Days(-99_999_999_999_999).apply(today), None);
+        assert_eq!(DateOffset::Weeks(i64::MAX).apply(today), None);
+        assert_eq!(DateOffset::Weeks(i64::MIN).apply(today), None);
+        assert_eq!(DateOffset::Months(i64::MAX).apply(today), None);
+        assert_eq!(DateOffset::Years(i64::MAX).apply(today), None);
+    }
+}
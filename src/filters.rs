@@ -0,0 +1,437 @@
+use chrono::{Datelike, NaiveDate, Weekday};
+use std::ops::{Bound, RangeBounds};
+use std::process;
+
+use crate::anniversary;
+use crate::categories;
+use crate::date_expr::{self, parse_date_or_exit};
+use crate::date_offset::DateOffset;
+use crate::precision::Precision;
+use crate::search::Search;
+use crate::{separate_args_to_vector, Event};
+
+/// Select the events matching the list options, like `--today` or `--categories a,b --exclude`.
+/// The options are given without the command and can be combined, an event has to match
+/// all of them. No options selects every event.
+pub fn filter_events<'a>(
+    options: &[String],
+    events: &'a [Event],
+    now: NaiveDate,
+) -> Vec<&'a Event> {
+    let (search, options) = Search::from_options(options);
+    let selected = filter_by_options(&options, events, now);
+
+    match search {
+        Some(search) => selected
+            .into_iter()
+            .filter(|e| search.is_match(&e.description))
+            .collect(),
+        None => selected,
+    }
+}
+
+fn filter_by_options<'a>(
+    options: &[String],
+    events: &'a [Event],
+    now: NaiveDate,
+) -> Vec<&'a Event> {
+    let arg_today = "--today";
+    let arg_before_date = "--before-date";
+    let arg_after_date = "--after-date";
+    let arg_date = "--date";
+    let arg_between = "--between";
+    let arg_inclusive = "--inclusive";
+    let arg_exclusive = "--exclusive";
+    let arg_categories = "--categories";
+    let arg_exclude = "--exclude";
+    let arg_no_category = "--no-category";
+    let arg_week = "--week";
+    let arg_month = "--month";
+    let arg_quarter = "--quarter";
+    let arg_year = "--year";
+    let arg_weekday = "--weekday";
+    let arg_tags = "--tags";
+    let arg_any = "--any";
+    let arg_all = "--all";
+    let arg_anniversaries = "--anniversaries";
+    let arg_next = "--next";
+
+    // Start from all events, every option narrows down the selection
+    let mut selected: Vec<&Event> = events.iter().collect();
+    let mut has_range = false;
+
+    let mut i = 0;
+    while i < options.len() {
+        let option = options[i].as_str();
+
+        // Events by today
+        if option == arg_today {
+            selected.retain(|e| e.is_within(now, now));
+            i += 1;
+        // Events on a date
+        } else if option == arg_date {
+            let date = parse_date_or_exit(option_value(options, i), now);
+            selected.retain(|e| e.is_within(date, date));
+            i += 2;
+        // Events by before, after, both or between, handled together below
+        } else if option == arg_before_date || option == arg_after_date {
+            has_range = true;
+            i += 2;
+        } else if option == arg_between {
+            has_range = true;
+            i += 3;
+        } else if option == arg_inclusive || option == arg_exclusive {
+            i += 1;
+        // Events with any or all of the tags, any by default
+        } else if option == arg_tags {
+            let tags = separate_args_to_vector(option_value(options, i));
+            let all = options.iter().any(|o| o == arg_all);
+            selected.retain(|e| {
+                if all {
+                    tags.iter().all(|tag| e.has_tag(tag))
+                } else {
+                    tags.iter().any(|tag| e.has_tag(tag))
+                }
+            });
+            i += 2;
+        } else if option == arg_any || option == arg_all {
+            i += 1;
+        // Past events with an anniversary and year-less dates in the next 30 days or --next
+        } else if option == arg_anniversaries {
+            let window = match options.iter().position(|o| o == arg_next) {
+                Some(next) => DateOffset::parse(option_value(options, next)).unwrap_or_else(|| {
+                    eprintln!("Bad offset given: {}", options[next + 1]);
+                    process::exit(1);
+                }),
+                None => DateOffset::Days(30),
+            };
+            let last = window.apply(now).unwrap_or(now);
+            selected.retain(|e| match e.precision {
+                Precision::Day => {
+                    e.date < now
+                        && anniversary::next(e.date, now).is_some_and(|(date, _)| date <= last)
+                }
+                // Already on their next day
+                Precision::MonthDay { .. } => e.date <= last,
+                Precision::Month | Precision::Year => false,
+            });
+            i += 1;
+        } else if option == arg_next {
+            if !options.iter().any(|o| o == arg_anniversaries) {
+                eprintln!("--next is for --anniversaries, like --anniversaries --next 30d");
+                process::exit(1);
+            }
+            i += 2;
+        // Events in a week, month, quarter or year
+        } else if option == arg_week
+            || option == arg_month
+            || option == arg_quarter
+            || option == arg_year
+        {
+            let value = option_value(options, i);
+            match parse_period(option, value) {
+                Some((first, last)) => selected.retain(|e| e.is_within(first, last)),
+                None => {
+                    eprintln!(
+                        "Bad period given: {}, use for example --week 2023-W23, --month 2023-05, --quarter 2023-Q2 or --year 2023",
+                        value
+                    );
+                    process::exit(1);
+                }
+            }
+            i += 2;
+        // Events on weekdays
+        } else if option == arg_weekday {
+            let mut weekdays = Vec::new();
+            for weekday in separate_args_to_vector(option_value(options, i)) {
+                match weekday.trim().parse::<Weekday>() {
+                    Ok(weekday) => weekdays.push(weekday),
+                    Err(_) => {
+                        eprintln!("Bad weekday given: {}", weekday);
+                        process::exit(1);
+                    }
+                }
+            }
+            selected.retain(|e| e.precision.has_day() && weekdays.contains(&e.date.weekday()));
+            i += 2;
+        // Events without a category
+        } else if option == arg_no_category {
+            selected.retain(|e| e.category.is_empty());
+            i += 1;
+        // Events by categories
+        } else if option == arg_categories {
+            // Separate the categories into a vector
+            let categories: Vec<String> = separate_args_to_vector(option_value(options, i));
+            // Check if user gave --exclude argument
+            let exclude: bool = options.len() > i + 2 && options[i + 2] == arg_exclude;
+
+            // Aliases and child categories match too, so work matches work/release
+            let registry = categories::get();
+            selected
+                .retain(|e| categories.iter().any(|c| registry.matches(&e.category, c)) != exclude);
+            i += if exclude { 3 } else { 2 };
+        } else {
+            eprintln!("Unknown option: {}", option);
+            process::exit(1);
+        }
+    }
+
+    if has_range {
+        let range = parse_date_range(options, now);
+        selected.retain(|e| in_range(e, range));
+    }
+
+    selected
+}
+
+/// Whether the event is in the date range. Months and years are in it if their last day
+/// is after the start and their first day before the end
+pub fn in_range(event: &Event, range: (Bound<NaiveDate>, Bound<NaiveDate>)) -> bool {
+    let (start, end) = range;
+    let (first, last) = event.precision.span(event.date);
+    (start, Bound::Unbounded).contains(&last) && (Bound::Unbounded, end).contains(&first)
+}
+
+/// The first and last day of --week, --month, --quarter or --year with the value
+fn parse_period(option: &str, value: &str) -> Option<(NaiveDate, NaiveDate)> {
+    match option {
+        "--week" => date_expr::parse_week(value),
+        "--month" => date_expr::parse_month(value),
+        "--quarter" => date_expr::parse_quarter(value),
+        "--year" => date_expr::parse_year(value),
+        _ => None,
+    }
+}
+
+/// The years the date options cover, for adding the holidays to the events. An open
+/// start is the current year and an open end the next year, unless the other end is past
+/// them, so a plain list gets the holidays of those two years
+pub fn shown_years(options: &[String], now: NaiveDate) -> (i32, i32) {
+    let mut first: Option<NaiveDate> = None;
+    let mut last: Option<NaiveDate> = None;
+    let mut narrow = |start: Option<NaiveDate>, end: Option<NaiveDate>| {
+        first = first.max(start);
+        last = match (last, end) {
+            (Some(last), Some(end)) => Some(last.min(end)),
+            (last, end) => last.or(end),
+        };
+    };
+
+    for (i, option) in options.iter().enumerate() {
+        let value = options.get(i + 1).map(|value| value.as_str()).unwrap_or("");
+        if option == "--today" {
+            narrow(Some(now), Some(now));
+        } else if option == "--date" {
+            let date = date_expr::parse_date_expression(value, now);
+            narrow(date, date);
+        } else if let Some((start, end)) = parse_period(option, value) {
+            narrow(Some(start), Some(end));
+        }
+    }
+    let bound_date = |bound: Bound<NaiveDate>| match bound {
+        Bound::Included(date) | Bound::Excluded(date) => Some(date),
+        Bound::Unbounded => None,
+    };
+    let (start, end) = parse_date_range(options, now);
+    narrow(bound_date(start), bound_date(end));
+
+    let first_year = first.map_or(now.year(), |first| first.year());
+    let last_year = last.map_or(now.year() + 1, |last| last.year());
+    match (first, last) {
+        (None, Some(_)) => (first_year.min(last_year), last_year),
+        (Some(_), None) => (first_year, last_year.max(first_year)),
+        _ => (first_year, last_year),
+    }
+}
+
+/// The value after the option at index i, exit with an error message if it's missing
+fn option_value(options: &[String], i: usize) -> &str {
+    match options.get(i + 1) {
+        Some(value) => value,
+        None => {
+            eprintln!("No value given for {}", options[i]);
+            process::exit(1);
+        }
+    }
+}
+
+/// Parse the date range from --before-date, --after-date and --between, in any order.
+/// When several are given, the range is their intersection.
+/// --before-date and --after-date are exclusive and --between is inclusive by default,
+/// --inclusive or --exclusive change all the bounds. Dates can be expressions like +2w
+pub fn parse_date_range(
+    options: &[String],
+    now: NaiveDate,
+) -> (Bound<NaiveDate>, Bound<NaiveDate>) {
+    let arg_before_date = "--before-date";
+    let arg_after_date = "--after-date";
+    let arg_between = "--between";
+    let arg_inclusive = "--inclusive";
+    let arg_exclusive = "--exclusive";
+
+    let inclusive = options.iter().any(|o| o == arg_inclusive);
+    let exclusive = options.iter().any(|o| o == arg_exclusive);
+    if inclusive && exclusive {
+        eprintln!("Give only one of --inclusive and --exclusive");
+        process::exit(1);
+    }
+
+    // Dates and whether they are inclusive
+    let mut start: Option<(NaiveDate, bool)> = None;
+    let mut end: Option<(NaiveDate, bool)> = None;
+
+    let mut i = 0;
+    while i < options.len() {
+        if options[i] == arg_before_date || options[i] == arg_after_date {
+            if i + 1 >= options.len() {
+                eprintln!("No date given for {}", options[i]);
+                process::exit(1);
+            }
+            let date = parse_date_or_exit(&options[i + 1], now);
+            if options[i] == arg_before_date {
+                end = narrower_end(end, (date, inclusive));
+            } else {
+                start = narrower_start(start, (date, inclusive));
+            }
+            i += 2;
+        } else if options[i] == arg_between {
+            if i + 2 >= options.len() {
+                eprintln!("Two dates are needed for --between");
+                process::exit(1);
+            }
+            let first = parse_date_or_exit(&options[i + 1], now);
+            let last = parse_date_or_exit(&options[i + 2], now);
+            if first > last {
+                eprintln!("Start date {} is after end date {}", first, last);
+                process::exit(1);
+            }
+            start = narrower_start(start, (first, !exclusive));
+            end = narrower_end(end, (last, !exclusive));
+            i += 3;
+        } else {
+            i += 1;
+        }
+    }
+
+    let to_bound = |bound: Option<(NaiveDate, bool)>| match bound {
+        Some((date, true)) => Bound::Included(date),
+        Some((date, false)) => Bound::Excluded(date),
+        None => Bound::Unbounded,
+    };
+    (to_bound(start), to_bound(end))
+}
+
+/// Pick the later of two start bounds, exclusive wins on the same date
+fn narrower_start(
+    current: Option<(NaiveDate, bool)>,
+    new: (NaiveDate, bool),
+) -> Option<(NaiveDate, bool)> {
+    match current {
+        Some(current) if current.0 > new.0 || (current.0 == new.0 && !current.1) => Some(current),
+        _ => Some(new),
+    }
+}
+
+/// Pick the earlier of two end bounds, exclusive wins on the same date
+fn narrower_end(
+    current: Option<(NaiveDate, bool)>,
+    new: (NaiveDate, bool),
+) -> Option<(NaiveDate, bool)> {
+    match current {
+        Some(current) if current.0 < new.0 || (current.0 == new.0 && !current.1) => Some(current),
+        _ => Some(new),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::date;
+
+    fn options(options: &str) -> Vec<String> {
+        options.split_whitespace().map(String::from).collect()
+    }
+
+    fn range(given: &str) -> (Bound<NaiveDate>, Bound<NaiveDate>) {
+        parse_date_range(&options(given), date(2023, 5, 10))
+    }
+
+    #[test]
+    fn between_is_inclusive_and_before_after_exclusive() {
+        assert_eq!(
+            range("--between 2023-05-01 2023-05-31"),
+            (
+                Bound::Included(date(2023, 5, 1)),
+                Bound::Included(date(2023, 5, 31))
+            )
+        );
+        assert_eq!(
+            range("--after-date 2023-05-01 --before-date 2023-05-31"),
+            (
+                Bound::Excluded(date(2023, 5, 1)),
+                Bound::Excluded(date(2023, 5, 31))
+            )
+        );
+        assert_eq!(range(""), (Bound::Unbounded, Bound::Unbounded));
+    }
+
+    #[test]
+    fn inclusive_and_exclusive_change_all_bounds() {
+        assert_eq!(
+            range("--after-date 2023-05-01 --inclusive"),
+            (Bound::Included(date(2023, 5, 1)), Bound::Unbounded)
+        );
+        assert_eq!(
+            range("--between 2023-05-01 2023-05-31 --exclusive"),
+            (
+                Bound::Excluded(date(2023, 5, 1)),
+                Bound::Excluded(date(2023, 5, 31))
+            )
+        );
+    }
+
+    #[test]
+    fn several_ranges_are_intersected() {
+        assert_eq!(
+            range(
+                "--between 2023-05-01 2023-05-31 --after-date 2023-05-10 --before-date 2023-06-30"
+            ),
+            (
+                Bound::Excluded(date(2023, 5, 10)),
+                Bound::Included(date(2023, 5, 31))
+            )
+        );
+        // On the same date the exclusive bound is narrower
+        assert_eq!(
+            range("--between 2023-05-01 2023-05-31 --after-date 2023-05-01"),
+            (
+                Bound::Excluded(date(2023, 5, 1)),
+                Bound::Included(date(2023, 5, 31))
+            )
+        );
+        assert_eq!(
+            range("--after-date +1d --after-date yesterday"),
+            (Bound::Excluded(date(2023, 5, 11)), Bound::Unbounded)
+        );
+    }
+
+    #[test]
+    fn shown_years_follow_the_date_options() {
+        let now = date(2023, 5, 10);
+        assert_eq!(shown_years(&options(""), now), (2023, 2024));
+        assert_eq!(shown_years(&options("--today"), now), (2023, 2023));
+        assert_eq!(shown_years(&options("--year 2030"), now), (2030, 2030));
+        assert_eq!(
+            shown_years(&options("--before-date 2000-03-01"), now),
+            (2000, 2000)
+        );
+        assert_eq!(
+            shown_years(&options("--after-date 1990-01-01"), now),
+            (1990, 2024)
+        );
+        assert_eq!(
+            shown_years(&options("--between 2020-01-01 2030-01-01 --year 2025"), now),
+            (2025, 2025)
+        );
+    }
+}
@@ -1,14 +1,53 @@
 pub fn help_list() {
     println!("Help for the list command:");
     println!("Usage: days list [options]");
+    println!("Options can be combined, like --categories school --between 2023-05-01 2023-05-31");
     println!("Options:");
     println!("--today");
     println!("--before-date <date>");
     println!("--after-date <date>");
     println!("--date <date>");
-    println!("--categories <category1,category2>");
+    println!("--between <date> <date>");
+    println!("--before-date <date> --after-date <date> (events between the dates)");
+    println!("Dates can be 2023-05-10, 10.5.2023, 2023-W19-3, today, tomorrow, yesterday, +3d, -2w, 'next friday' or 'end of month'");
+    println!(
+        "Relative dates are counted from today, which can be changed with DAYS_TODAY=2023-05-10"
+    );
+    println!("Add --inclusive or --exclusive to include or exclude the given dates. By default --between includes them, --before-date and --after-date don't.");
+    println!("--week <2023-W23>");
+    println!("--month <2023-05>");
+    println!("--quarter <2023-Q2>");
+    println!("--year <2023>");
+    println!("--weekday <sat,sun>");
+    println!("--categories <category1,category2> (aliases and child categories match too)");
     println!("--exclude <category1,category2>");
     println!("--no-category");
+    println!("--tags <tag1,tag2> [--any | --all] (events with any of the tags by default)");
+    println!("--search <text> [--ignore-case] [--regex] [--fuzzy]");
+    println!(
+        "The search can be added after any other option, like --categories school --search course"
+    );
+    println!("--anniversaries [--next <30d>] (past events with an anniversary in the next 30 days or --next, nearest first)");
+    println!("  they are shown like '37 years ago, 38th anniversary in 120 days', or always with anniversaries = true in the config");
+    println!("--ids shows the ids of the events for the show command");
+    println!("--limit <n> shows at most n events and --offset <n> skips the first n, like --offset 20 --limit 20");
+    println!("--count prints only the number of the events, holidays included, --categories holidays --exclude leaves them out");
+    println!("Lists longer than the terminal are shown with $PAGER or less, PAGER= turns it off");
+    println!("With holidays = \"fi\" in the config the Finnish holidays of this and the next year are shown too,");
+    println!(
+        "or of the years of the date options like --year 2030. They are in agenda and cal too."
+    );
+    println!("They are in the holidays category, so --exclude holidays hides them.");
+    println!("--business-days counts the days to the events in working days, without the weekend from the config,");
+    println!("the holidays and the days of the categories marked non-working in categories.csv");
+}
+
+pub fn help_show() {
+    println!("Help for the show command:");
+    println!("Usage: days show <id>");
+    println!("Shows all the details of the event, like the notes, location, URL and meta.");
+    println!("The id is the position of the event in events.csv, shown with 'days list --ids'.");
+    println!("The ids change when events before it are deleted.");
 }
 
 pub fn help_add() {
@@ -18,6 +57,16 @@ pub fn help_add() {
     println!("--date <date> --category <category> --description <description>");
     println!("--category <category> --description <description>");
     println!("--description <description>");
+    println!("--tag <tag> can be given many times, like --tag rust --tag backend");
+    println!("--notes <notes> (can span several lines)");
+    println!("--location <location>");
+    println!("--url <url>");
+    println!("--meta <key=value> can be given many times, like --meta room=B204");
+    println!("--remind <7d,1d> (reminders 7 days and 1 day before, see 'days --help remind')");
+    println!("Dates can be 2023-05-10, 10.5.2023, 2023-W19-3, today, tomorrow, +3d, -2w, 'next friday' or 'end of month'");
+    println!(
+        "Partial dates: 2023-05 (a month), 1985 (a year) or --05-10 (every year, like a birthday)"
+    );
 }
 
 pub fn help_delete() {
@@ -30,4 +79,143 @@ pub fn help_delete() {
     println!("--date <date> --category <category>");
     println!("--description <description>");
     println!("--date <date> --category <category> --description <description>");
+    println!("--date matches the date like it's stored, so --date 2023-05-12 doesn't delete a yearly --05-12, --date --05-12 does");
+    println!("--between <date> <date> [--exclusive]");
+    println!("--search <text> [--ignore-case] [--regex] [--fuzzy]");
+}
+
+pub fn help_shift() {
+    println!("Help for the shift command:");
+    println!("Usage: days shift [list options] --by <offset>");
+    println!("Moves the events selected with the list options by the offset. Add --dry-run to see the changes first.");
+    println!("Offsets: +14d (days), -2w (weeks), +1m (months), +1y (years), +3wd (working days)");
+    println!("Months like 2023-05 are shifted by months or years, years like 1985 by years and year-less dates like --05-10 not by years,");
+    println!("selected events that the offset can't move are left out");
+    println!("Example: days shift --categories school,release --by +14d --dry-run");
+}
+
+pub fn help_stats() {
+    println!("Help for the stats command:");
+    println!("Usage: days stats [list options] [--json]");
+    println!("Shows the number of events per category, year and month, the first and last event and the busiest days.");
+    println!("The list options, like --year 2023, select which events are counted.");
+    println!(
+        "Years like 1985 are only counted per year, months like 2023-05 per year and month and"
+    );
+    println!("year-less dates like --05-10 only per category.");
+    println!("--json prints the stats as JSON");
+}
+
+pub fn help_cal() {
+    println!("Help for the cal command:");
+    println!("Usage: days cal [--month <2023-05>] [--year <2023>] [list options] [--legend]");
+    println!("Shows a calendar of this month, the given month or the given year.");
+    println!("Days with events are marked with *, on terminals they are colored by category and today is highlighted.");
+    println!("--legend lists the events below the calendar");
+}
+
+pub fn help_agenda() {
+    println!("Help for the agenda command:");
+    println!("Usage: days agenda [--next <14d>] [--past <3d>] [--limit <N>] [list options]");
+    println!("Shows the events around today grouped by day. By default the next 7 days are shown.");
+    println!("--next and --past take days, weeks or months, like 14d, 2w or 1m");
+    println!("--limit shows at most N events");
+    println!("--business-days counts the days in working days, like in the list command");
+}
+
+pub fn help_next() {
+    println!("Help for the next command:");
+    println!("Usage: days next [--category <category>] [--count <N>] [list options]");
+    println!(
+        "Shows the nearest upcoming event, today included. --count shows the N nearest events."
+    );
+    println!("Exits with status 1 if there are no upcoming events.");
+}
+
+pub fn help_config() {
+    println!("Help for the config command:");
+    println!("Usage: days config [list | get <key> | set <key> <value>]");
+    println!("The config is read from ~/.config/days/config.toml or ~/.days/config.toml, or DAYS_CONFIG if it's set.");
+    println!("'days config list' shows all the keys with their values and descriptions.");
+    println!("Example: days config set default_command 'agenda --next 14d'");
+    println!("Hooks: pre_add, post_add, pre_edit, post_edit, pre_delete and post_delete are shell commands");
+    println!("run before and after the events change. shift is an edit. The events are given as JSON on stdin:");
+    println!("{{\"action\": \"edit\", \"events\": [...], \"previous\": [...]}}");
+    println!("A pre hook that fails cancels the change.");
+}
+
+pub fn help_categories() {
+    println!("Help for the categories command:");
+    println!("Usage: days categories");
+    println!("Lists the categories with the number of events in each, child categories included.");
+    println!("Categories can be registered in categories.csv next to events.csv:");
+    println!("name,aliases,color,icon,working");
+    println!("computing,comp;Computing,blue,💻");
+    println!("work/release,release,bold green,🚀");
+    println!("vacation,,cyan,🌴,no");
+    println!("Aliases are separated with ; and names are matched ignoring case.");
+    println!("working is no for days off, they are skipped with --business-days.");
+    println!("--categories work matches work and its children like work/release.");
+}
+
+pub fn help_remind() {
+    println!("Help for the remind command:");
+    println!("Usage: days remind [--exec <command>] [--dry-run]");
+    println!("Prints the reminders that are due, added with 'days add --remind 7d,1d'.");
+    println!("Each reminder is printed once, the fired reminders are kept in reminders.csv next to events.csv.");
+    println!("Prints nothing when there are no reminders, so it can be run from cron, like:");
+    println!("*/30 * * * * days remind --exec notify-send");
+    println!("--exec runs the command for each reminder with the reminder as the last argument,");
+    println!("and DAYS_DATE, DAYS_CATEGORY and DAYS_DESCRIPTION set. If the command fails the reminder fires again the next time.");
+    println!("--dry-run shows the reminders without marking them fired");
+}
+
+pub fn help_prompt() {
+    println!("Help for the prompt command:");
+    println!("Usage: days prompt [--template <template>]");
+    println!("Prints a short line for shell prompts and status bars, nothing if there are no events today or later.");
+    println!("The template is prompt_template from the config, by default 'today: {{today}} / next: {{next}} in {{next_days}}d'");
+    println!("Placeholders: {{today}}, {{today_count}}, {{next}}, {{next_date}}, {{next_days}} and {{next_delta}}");
+    println!(
+        "The output is cached in ~/.cache/days/prompt until events.csv changes or the day changes."
+    );
+    println!("Example for bash: PS1='$(days prompt --template \"🎉 {{today}} / next: {{next}} in {{next_days}}d\") \\$ '");
+}
+
+pub fn help_bar() {
+    println!("Help for the bar command:");
+    println!("Usage: days bar [--protocol waybar|i3bar] [--next <offset>] [--watch]");
+    println!("Prints the events as JSON for status bars. The text is the prompt template, see 'days --help prompt',");
+    println!("the tooltip is the agenda of the next week or --next, like --next 14d.");
+    println!("The class is today if there are events today, soon if there are events in the next week, otherwise none.");
+    println!("--watch keeps running and prints the status again at midnight and when events.csv changes.");
+    println!("Example for waybar: \"custom/days\": {{\"exec\": \"days bar --watch\", \"return-type\": \"json\"}}");
+    println!("For polybar use 'days prompt' in a custom/script module.");
+}
+
+pub fn help_diff() {
+    println!("Help for the diff command:");
+    println!("Usage: days diff <date> <date>");
+    println!(
+        "Shows the time between the dates in days, working days, weeks and years, months and days."
+    );
+    println!("Working days are the days outside the weekend and the holidays of the config, counted after the first date up to the second.");
+    println!("The dates can be any dates of the list command, like today, 'next friday' or +3w.");
+    println!("Example: days diff 2023-05-10 2038-01-19");
+}
+
+pub fn help_calc() {
+    println!("Help for the calc command:");
+    println!("Usage: days calc <date> [offset...]");
+    println!("Shows the date moved by the offsets, with the weekday and the days from today.");
+    println!("Offsets: +14d (days), -2w (weeks), +1m (months), +1y (years), +3wd (working days)");
+    println!("Example: days calc 2023-05-10 +90d, days calc today +1m -3wd");
+}
+
+pub fn help_on_this_day() {
+    println!("Help for the onthisday command:");
+    println!("Usage: days onthisday [--date <MM-DD>]");
+    println!("Lists the events of previous years on today's month and day, or on --date, grouped by year.");
+    println!("February 29 events are shown on February 28 in other years, leap_day in the config");
+    println!("can change it to mar1 or skip.");
 }
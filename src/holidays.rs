@@ -0,0 +1,204 @@
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+
+use crate::categories;
+use crate::config;
+use crate::locale::Locale;
+use crate::Event;
+
+/// Category of the holidays shown with the events
+pub const CATEGORY: &str = "holidays";
+
+/// A calendar of holidays that can be computed for any year.
+/// To add a country implement this and add it to provider()
+pub trait HolidayProvider {
+    /// The holidays of the year with their names in the language of the locale
+    fn holidays(&self, year: i32, locale: Locale) -> Vec<(NaiveDate, &'static str)>;
+}
+
+/// The provider by the name used in the config, like fi
+pub fn provider(name: &str) -> Option<Box<dyn HolidayProvider>> {
+    match name.trim().to_lowercase().as_str() {
+        "fi" | "finland" => Some(Box::new(Finland)),
+        _ => None,
+    }
+}
+
+/// Finnish public holidays, with Midsummer Eve and Christmas Eve which are days off too
+pub struct Finland;
+
+impl HolidayProvider for Finland {
+    fn holidays(&self, year: i32, locale: Locale) -> Vec<(NaiveDate, &'static str)> {
+        let easter = match easter_sunday(year) {
+            Some(easter) => easter,
+            None => return Vec::new(),
+        };
+        let date = |month: u32, day: u32| NaiveDate::from_ymd_opt(year, month, day);
+        // Midsummer Day is the Saturday between 20 and 26 June,
+        // All Saints' Day the Saturday between 31 October and 6 November
+        let midsummer = date(6, 20).map(|first| next_weekday(first, Weekday::Sat));
+        let all_saints = date(10, 31).map(|first| next_weekday(first, Weekday::Sat));
+
+        let holidays = [
+            (date(1, 1), "Uudenvuodenpäivä", "New Year's Day"),
+            (date(1, 6), "Loppiainen", "Epiphany"),
+            (
+                Some(easter - Duration::days(2)),
+                "Pitkäperjantai",
+                "Good Friday",
+            ),
+            (Some(easter), "Pääsiäispäivä", "Easter Sunday"),
+            (
+                Some(easter + Duration::days(1)),
+                "2. pääsiäispäivä",
+                "Easter Monday",
+            ),
+            (date(5, 1), "Vappu", "May Day"),
+            (
+                Some(easter + Duration::days(39)),
+                "Helatorstai",
+                "Ascension Day",
+            ),
+            (
+                Some(easter + Duration::days(49)),
+                "Helluntaipäivä",
+                "Whit Sunday",
+            ),
+            (
+                midsummer.map(|d| d - Duration::days(1)),
+                "Juhannusaatto",
+                "Midsummer Eve",
+            ),
+            (midsummer, "Juhannuspäivä", "Midsummer Day"),
+            (all_saints, "Pyhäinpäivä", "All Saints' Day"),
+            (date(12, 6), "Itsenäisyyspäivä", "Independence Day"),
+            (date(12, 24), "Jouluaatto", "Christmas Eve"),
+            (date(12, 25), "Joulupäivä", "Christmas Day"),
+            (date(12, 26), "Tapaninpäivä", "St. Stephen's Day"),
+        ];
+
+        holidays
+            .into_iter()
+            .filter_map(|(date, finnish, english)| {
+                let name = match locale {
+                    Locale::Finnish => finnish,
+                    Locale::English => english,
+                };
+                Some((date?, name))
+            })
+            .collect()
+    }
+}
+
+/// Easter Sunday of the Gregorian calendar, with the anonymous Gregorian computus
+pub fn easter_sunday(year: i32) -> Option<NaiveDate> {
+    let a = year.rem_euclid(19);
+    let b = year.div_euclid(100);
+    let c = year.rem_euclid(100);
+    let d = b / 4;
+    let e = b % 4;
+    let f = (b + 8) / 25;
+    let g = (b - f + 1) / 3;
+    let h = (19 * a + b - d - g + 15) % 30;
+    let i = c / 4;
+    let k = c % 4;
+    let l = (32 + 2 * e + 2 * i - h - k) % 7;
+    let m = (a + 11 * h + 22 * l) / 451;
+    let month = (h + l - 7 * m + 114) / 31;
+    let day = (h + l - 7 * m + 114) % 31 + 1;
+    NaiveDate::from_ymd_opt(year, month as u32, day as u32)
+}
+
+/// The first date on or after the date that is the weekday
+fn next_weekday(date: NaiveDate, weekday: Weekday) -> NaiveDate {
+    let days = (weekday.num_days_from_monday() + 7 - date.weekday().num_days_from_monday()) % 7;
+    date + Duration::days(days as i64)
+}
+
+/// The holidays of the providers in the config for the years, as events
+pub fn holiday_events(first_year: i32, last_year: i32) -> Vec<Event> {
+    let config = config::get();
+    let mut events = Vec::new();
+    for provider in config.holidays.iter().filter_map(|name| provider(name)) {
+        for year in first_year..=last_year {
+            for (date, name) in provider.holidays(year, config.locale) {
+                events.push(Event::new(date, CATEGORY.to_string(), name.to_string()));
+            }
+        }
+    }
+    events
+}
+
+/// The events with the holidays of the years that are shown. The events stay in their order
+/// and each holiday goes before the first event after it. Holidays that are already in
+/// events.csv, with the same name or in the holidays category, aren't added again
+pub fn with_holidays(events: &[Event], first_year: i32, last_year: i32) -> Vec<Event> {
+    if config::get().holidays.is_empty() {
+        return events.to_vec();
+    }
+
+    let mut holidays: Vec<Event> = holiday_events(first_year, last_year)
+        .into_iter()
+        .filter(|holiday| {
+            !events.iter().any(|e| {
+                e.date == holiday.date
+                    && (e.description.to_lowercase() == holiday.description.to_lowercase()
+                        || categories::get().matches(&e.category, CATEGORY))
+            })
+        })
+        .collect();
+    holidays.sort_by_key(|holiday| holiday.date);
+
+    let mut all = Vec::with_capacity(events.len() + holidays.len());
+    let mut holidays = holidays.into_iter().peekable();
+    for event in events {
+        while let Some(holiday) = holidays.next_if(|holiday| holiday.date < event.date) {
+            all.push(holiday);
+        }
+        all.push(event.clone());
+    }
+    all.extend(holidays);
+    all
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::date;
+
+    fn holiday(year: i32, name: &str) -> Option<NaiveDate> {
+        Finland
+            .holidays(year, Locale::English)
+            .into_iter()
+            .find(|(_, holiday)| *holiday == name)
+            .map(|(date, _)| date)
+    }
+
+    #[test]
+    fn computes_easter() {
+        assert_eq!(easter_sunday(2000), Some(date(2000, 4, 23)));
+        assert_eq!(easter_sunday(2019), Some(date(2019, 4, 21)));
+        assert_eq!(easter_sunday(2023), Some(date(2023, 4, 9)));
+        assert_eq!(easter_sunday(2024), Some(date(2024, 3, 31)));
+        assert_eq!(easter_sunday(2038), Some(date(2038, 4, 25)));
+    }
+
+    #[test]
+    fn computes_finnish_holidays() {
+        assert_eq!(holiday(2023, "Good Friday"), Some(date(2023, 4, 7)));
+        assert_eq!(holiday(2023, "Ascension Day"), Some(date(2023, 5, 18)));
+        assert_eq!(holiday(2023, "Whit Sunday"), Some(date(2023, 5, 28)));
+        assert_eq!(holiday(2023, "Midsummer Eve"), Some(date(2023, 6, 23)));
+        assert_eq!(holiday(2023, "Midsummer Day"), Some(date(2023, 6, 24)));
+        assert_eq!(holiday(2024, "Midsummer Eve"), Some(date(2024, 6, 21)));
+        assert_eq!(holiday(2023, "All Saints' Day"), Some(date(2023, 11, 4)));
+        assert_eq!(holiday(2026, "All Saints' Day"), Some(date(2026, 10, 31)));
+        assert_eq!(Finland.holidays(2023, Locale::English).len(), 15);
+    }
+
+    #[test]
+    fn names_follow_the_locale() {
+        assert!(Finland
+            .holidays(2023, Locale::Finnish)
+            .contains(&(date(2023, 6, 23), "Juhannusaatto")));
+    }
+}
@@ -0,0 +1,89 @@
+use serde_json::json;
+use std::io::Write;
+use std::process::{self, Command, Stdio};
+
+use crate::config;
+use crate::Event;
+
+/// The hooks that can be set in the config, the commands are run with the shell
+pub const HOOKS: [&str; 6] = [
+    "pre_add",
+    "post_add",
+    "pre_edit",
+    "post_edit",
+    "pre_delete",
+    "post_delete",
+];
+
+/// Run the pre hook of the action, like pre_add. If the hook fails the change is cancelled
+/// and the program exits
+pub fn run_pre(action: &str, events: &[&Event], previous: &[&Event]) {
+    let hook = format!("pre_{}", action);
+    if !run(&hook, action, events, previous) {
+        eprintln!("Cancelled by the {} hook", hook);
+        process::exit(1);
+    }
+}
+
+/// Run the post hook of the action, like post_add. The change has already been made,
+/// so a failing hook is only reported
+pub fn run_post(action: &str, events: &[&Event], previous: &[&Event]) {
+    run(&format!("post_{}", action), action, events, previous);
+}
+
+/// Run the hook command with the events as JSON on stdin:
+///
+/// {"action": "edit", "events": [...], "previous": [...]}
+///
+/// previous has the events before an edit, in the same order as events.
+/// Returns whether the hook succeeded, a hook that isn't set always succeeds
+fn run(hook: &str, action: &str, events: &[&Event], previous: &[&Event]) -> bool {
+    let command = match config::get().hooks.get(hook) {
+        Some(command) => command,
+        None => return true,
+    };
+
+    let mut input = json!({
+        "action": action,
+        "events": events.iter().map(|e| e.to_json()).collect::<Vec<_>>(),
+    });
+    if !previous.is_empty() {
+        input["previous"] = previous.iter().map(|e| e.to_json()).collect();
+    }
+
+    let shell = if cfg!(windows) {
+        Command::new("cmd")
+            .args(["/C", command])
+            .stdin(Stdio::piped())
+            .spawn()
+    } else {
+        Command::new("sh")
+            .args(["-c", command])
+            .stdin(Stdio::piped())
+            .spawn()
+    };
+    let mut child = match shell {
+        Ok(child) => child,
+        Err(e) => {
+            eprintln!("Error running the {} hook: {}", hook, e);
+            return false;
+        }
+    };
+
+    // The hook doesn't have to read the input, so a closed stdin isn't an error
+    if let Some(mut stdin) = child.stdin.take() {
+        let _ = writeln!(stdin, "{}", input);
+    }
+
+    match child.wait() {
+        Ok(status) if status.success() => true,
+        Ok(status) => {
+            eprintln!("The {} hook failed: {}", hook, status);
+            false
+        }
+        Err(e) => {
+            eprintln!("Error running the {} hook: {}", hook, e);
+            false
+        }
+    }
+}
@@ -0,0 +1,212 @@
+use chrono::{Datelike, NaiveDate, Weekday};
+
+/// Language of the printed texts
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Locale {
+    English,
+    Finnish,
+}
+
+const MONTHS_EN: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+
+const MONTHS_FI: [&str; 12] = [
+    "tammikuu",
+    "helmikuu",
+    "maaliskuu",
+    "huhtikuu",
+    "toukokuu",
+    "kesäkuu",
+    "heinäkuu",
+    "elokuu",
+    "syyskuu",
+    "lokakuu",
+    "marraskuu",
+    "joulukuu",
+];
+
+/// Monday first
+const WEEKDAYS_EN: [&str; 7] = [
+    "Monday",
+    "Tuesday",
+    "Wednesday",
+    "Thursday",
+    "Friday",
+    "Saturday",
+    "Sunday",
+];
+
+const WEEKDAYS_FI: [&str; 7] = [
+    "maanantai",
+    "tiistai",
+    "keskiviikko",
+    "torstai",
+    "perjantai",
+    "lauantai",
+    "sunnuntai",
+];
+
+const WEEKDAYS_SHORT_EN: [&str; 7] = ["Mo", "Tu", "We", "Th", "Fr", "Sa", "Su"];
+const WEEKDAYS_SHORT_FI: [&str; 7] = ["ma", "ti", "ke", "to", "pe", "la", "su"];
+
+impl Locale {
+    /// en or fi
+    pub fn parse(locale: &str) -> Option<Self> {
+        match locale.to_lowercase().as_str() {
+            "en" | "en_us" | "en_gb" => Some(Locale::English),
+            "fi" | "fi_fi" => Some(Locale::Finnish),
+            _ => None,
+        }
+    }
+
+    pub fn month_name(&self, month: u32) -> &'static str {
+        let index = month as usize - 1;
+        match self {
+            Locale::English => MONTHS_EN[index],
+            Locale::Finnish => MONTHS_FI[index],
+        }
+    }
+
+    pub fn weekday_name(&self, weekday: Weekday) -> &'static str {
+        let index = weekday.num_days_from_monday() as usize;
+        match self {
+            Locale::English => WEEKDAYS_EN[index],
+            Locale::Finnish => WEEKDAYS_FI[index],
+        }
+    }
+
+    /// Two letter weekday, like Mo
+    pub fn weekday_short(&self, weekday: Weekday) -> &'static str {
+        let index = weekday.num_days_from_monday() as usize;
+        match self {
+            Locale::English => WEEKDAYS_SHORT_EN[index],
+            Locale::Finnish => WEEKDAYS_SHORT_FI[index],
+        }
+    }
+
+    /// today, yesterday, in X days or X days ago
+    pub fn delta(&self, delta: i64) -> String {
+        match (self, delta) {
+            (Locale::English, 0) => "today".to_string(),
+            (Locale::English, -1) => "yesterday".to_string(),
+            (Locale::English, d) if d < 0 => format!("{} days ago", d.abs()),
+            (Locale::English, d) => format!("in {} days", d),
+            (Locale::Finnish, 0) => "tänään".to_string(),
+            (Locale::Finnish, -1) => "eilen".to_string(),
+            (Locale::Finnish, d) if d < 0 => format!("{} päivää sitten", d.abs()),
+            (Locale::Finnish, d) => format!("{} päivän päästä", d),
+        }
+    }
+
+    /// in X working days or X working days ago
+    pub fn working_delta(&self, delta: i64) -> String {
+        match (self, delta) {
+            (Locale::English, d) if d < 0 => format!("{} working days ago", d.abs()),
+            (Locale::English, d) => format!("in {} working days", d),
+            (Locale::Finnish, d) if d < 0 => format!("{} työpäivää sitten", d.abs()),
+            (Locale::Finnish, d) => format!("{} työpäivän päästä", d),
+        }
+    }
+
+    /// this month, next month, last month, in N months or N months ago
+    pub fn months_delta(&self, months: i32) -> String {
+        match (self, months) {
+            (Locale::English, 0) => "this month".to_string(),
+            (Locale::English, 1) => "next month".to_string(),
+            (Locale::English, -1) => "last month".to_string(),
+            (Locale::English, m) if m < 0 => format!("{} months ago", m.abs()),
+            (Locale::English, m) => format!("in {} months", m),
+            (Locale::Finnish, 0) => "tässä kuussa".to_string(),
+            (Locale::Finnish, 1) => "ensi kuussa".to_string(),
+            (Locale::Finnish, -1) => "viime kuussa".to_string(),
+            (Locale::Finnish, m) if m < 0 => format!("{} kuukautta sitten", m.abs()),
+            (Locale::Finnish, m) => format!("{} kuukauden päästä", m),
+        }
+    }
+
+    /// this year, next year, last year, in N years or N years ago
+    pub fn years_delta(&self, years: i32) -> String {
+        match (self, years) {
+            (Locale::English, 0) => "this year".to_string(),
+            (Locale::English, 1) => "next year".to_string(),
+            (Locale::English, -1) => "last year".to_string(),
+            (Locale::English, y) if y < 0 => self.years_ago(y.abs()),
+            (Locale::English, y) => format!("in {} years", y),
+            (Locale::Finnish, 0) => "tänä vuonna".to_string(),
+            (Locale::Finnish, 1) => "ensi vuonna".to_string(),
+            (Locale::Finnish, -1) => "viime vuonna".to_string(),
+            (Locale::Finnish, y) if y < 0 => self.years_ago(y.abs()),
+            (Locale::Finnish, y) => format!("{} vuoden päästä", y),
+        }
+    }
+
+    /// 1 year ago or N years ago
+    pub fn years_ago(&self, years: i32) -> String {
+        match (self, years) {
+            (Locale::English, 1) => "1 year ago".to_string(),
+            (Locale::English, years) => format!("{} years ago", years),
+            (Locale::Finnish, 1) => "vuosi sitten".to_string(),
+            (Locale::Finnish, years) => format!("{} vuotta sitten", years),
+        }
+    }
+
+    /// Years since an event and its next anniversary, like 37 years ago, 38th anniversary in 120 days.
+    /// Within the first year only the anniversary, like 1st anniversary in 3 days
+    pub fn anniversary(&self, years: i32, nth: i32, days: i64) -> String {
+        let next = match self {
+            Locale::English => format!("{} anniversary {}", ordinal(nth), self.delta(days)),
+            Locale::Finnish => format!("{}. vuosipäivä {}", nth, self.delta(days)),
+        };
+        if years < 1 {
+            next
+        } else {
+            format!("{}, {}", self.years_ago(years), next)
+        }
+    }
+
+    /// Today, Tomorrow or Yesterday, None for other days
+    pub fn relative_day(&self, delta: i64) -> Option<&'static str> {
+        match (self, delta) {
+            (Locale::English, 0) => Some("Today"),
+            (Locale::English, 1) => Some("Tomorrow"),
+            (Locale::English, -1) => Some("Yesterday"),
+            (Locale::Finnish, 0) => Some("Tänään"),
+            (Locale::Finnish, 1) => Some("Huomenna"),
+            (Locale::Finnish, -1) => Some("Eilen"),
+            _ => None,
+        }
+    }
+
+    /// Weekday and date, like Friday 2023-05-12
+    pub fn weekday_and_date(&self, date: NaiveDate, date_format: &str) -> String {
+        format!(
+            "{} {}",
+            self.weekday_name(date.weekday()),
+            date.format(date_format)
+        )
+    }
+}
+
+/// English ordinal like 1st, 2nd, 3rd, 11th or 38th
+fn ordinal(n: i32) -> String {
+    let suffix = match (n % 10, n % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+    format!("{}{}", n, suffix)
+}
@@ -1,18 +1,59 @@
-use chrono::NaiveDate;
+use chrono::{Datelike, NaiveDate};
 use std::env;
-use std::fs::{File, OpenOptions};
-use std::io::{BufRead, BufReader, BufWriter, Error, Write};
+use std::fs::OpenOptions;
+use std::io::{BufWriter, Error, Write};
 use std::path::PathBuf;
 use std::process;
 
+mod agenda;
+mod anniversary;
+mod bar;
+mod business_days;
+mod cal;
+mod categories;
+mod colors;
+mod config;
+mod date_calc;
+mod date_expr;
+mod date_offset;
+mod filters;
 mod help_prints;
-
-#[derive(Debug)]
+mod holidays;
+mod hooks;
+mod locale;
+mod pager;
+mod precision;
+mod prompt;
+mod remind;
+mod search;
+mod stats;
+
+use date_offset::DateOffset;
+use precision::Precision;
+use search::Search;
+use stats::Stats;
+
+#[derive(Debug, Clone)]
 struct Event {
-    /// YYYY-MM-DD, like 2023-05-11
+    /// YYYY-MM-DD, like 2023-05-11. For partial dates the day they're sorted by,
+    /// see Precision
     date: NaiveDate,
+    /// Whether the date is a full date, a month, a year or a day without a year
+    precision: Precision,
     category: String,
     description: String,
+    /// Like rust and backend, stored separated with semicolons
+    tags: Vec<String>,
+    /// Can span several lines
+    notes: String,
+    location: String,
+    url: String,
+    /// Other details like room=B204, stored as key=value separated with semicolons
+    meta: Vec<(String, String)>,
+    /// Lead times of the reminders, like 7d and 1d, stored separated with semicolons
+    remind: Vec<String>,
+    /// Position of the event in events.csv starting from 1, 0 if it's not stored yet
+    id: usize,
 }
 
 // Create a new event
@@ -20,10 +61,61 @@ impl Event {
     fn new(date: NaiveDate, category: String, description: String) -> Self {
         Self {
             date,
+            precision: Precision::Day,
             category,
             description,
+            tags: Vec::new(),
+            notes: String::new(),
+            location: String::new(),
+            url: String::new(),
+            meta: Vec::new(),
+            remind: Vec::new(),
+            id: 0,
+        }
+    }
+
+    /// Whether the event is on a day between the first and the last date, both included.
+    /// Months and years are when any of their days is
+    fn is_within(&self, first: NaiveDate, last: NaiveDate) -> bool {
+        let (start, end) = self.precision.span(self.date);
+        start <= last && end >= first
+    }
+
+    /// Whether the event has the tag, ignoring case
+    fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t.eq_ignore_ascii_case(tag.trim()))
+    }
+
+    /// The tags for printing, like " [rust, backend]", or nothing if there are no tags
+    fn tags_display(&self) -> String {
+        if self.tags.is_empty() {
+            String::new()
+        } else {
+            format!(" [{}]", self.tags.join(", "))
         }
     }
+
+    /// The event as JSON, for the exports and scripts
+    fn to_json(&self) -> serde_json::Value {
+        let meta: serde_json::Map<String, serde_json::Value> = self
+            .meta
+            .iter()
+            .map(|(key, value)| (key.clone(), value.clone().into()))
+            .collect();
+        serde_json::json!({
+            "id": self.id,
+            "date": self.precision.format(self.date),
+            "precision": self.precision.name(),
+            "category": self.category,
+            "description": self.description,
+            "tags": self.tags,
+            "notes": self.notes,
+            "location": self.location,
+            "url": self.url,
+            "meta": meta,
+            "remind": self.remind,
+        })
+    }
 }
 
 #[derive(Debug)]
@@ -35,162 +127,415 @@ struct CsvData {
 
 /// Prints the event in the format
 /// YYYY-MM-DD: description (category) - in X days /  X days ago / today
-fn print_day_format(event: &Event) {
-    let delta = (event.date - chrono::Utc::now().naive_utc().date()).num_days();
-    let line = event.date.format("%Y-%m-%d").to_string()
-        + ": "
-        + &event.description
-        + " ("
-        + &event.category
-        + ") - "
-        + &format_delta(&delta);
-    println!("{}", line);
+/// The format can be changed with the template and date_format in the config.
+/// With colors past events are dimmed, today and the next week are highlighted,
+/// the category is colored and the text matching the search is highlighted
+fn print_day_format(event: &Event, search: Option<&Search>) {
+    println!("{}", day_format(event, search));
+}
+
+/// The line of print_day_format
+fn day_format(event: &Event, search: Option<&Search>) -> String {
+    let config = config::get();
+    let delta = (event.date - date_expr::current_date()).num_days();
+    let style = colors::delta_style(delta);
+    // Go back to the line's style after the differently colored parts
+    let back_to_style = colors::RESET.to_string() + style;
+
+    let description = match search {
+        Some(search) if colors::enabled() => search.highlight(
+            &event.description,
+            colors::highlight_style(),
+            &back_to_style,
+        ),
+        _ => event.description.clone(),
+    };
+    // Past events are dimmed all the way
+    let registry = categories::get();
+    let category = if colors::enabled() && delta >= 0 && !event.category.is_empty() {
+        colors::RESET.to_string()
+            + &registry.color(&event.category)
+            + &registry.display(&event.category)
+            + &back_to_style
+    } else {
+        registry.display(&event.category)
+    };
+
+    let line = fill_template(
+        &config.template,
+        &[
+            ("date", &event.precision.display(event.date)),
+            ("description", &description),
+            ("category", &category),
+            ("tags", &event.tags_display()),
+            ("delta", &event_delta(event, date_expr::current_date())),
+            ("id", &event.id.to_string()),
+        ],
+    );
+    colors::paint(&line, style)
+}
+
+/// Prints all the fields of the event, the empty ones are left out:
+///
+/// Event #5
+/// Date:        2023-05-10, Wednesday - today
+/// Description: Starting course work
+/// ...
+fn print_event_details(event: &Event) {
+    let config = config::get();
+    let today = date_expr::current_date();
+    let day = format!(
+        "{}, {} - {}",
+        event.date.format(&config.date_format),
+        config.locale.weekday_name(event.date.weekday()),
+        format_delta(&(event.date - today).num_days())
+    );
+    // Year-less dates with their next day, months and years in their own precision
+    let date = match (event.precision, event.precision.delta(event.date, today)) {
+        (Precision::Day, _) => day,
+        (Precision::MonthDay { .. }, _) => {
+            format!("{}, next {}", event.precision.display(event.date), day)
+        }
+        (_, delta) => format!(
+            "{} - {}",
+            event.precision.display(event.date),
+            delta.unwrap_or_default()
+        ),
+    };
+
+    println!("Event #{}", event.id);
+    let fields = [
+        ("Date", date),
+        ("Description", event.description.clone()),
+        ("Category", categories::get().display(&event.category)),
+        ("Tags", event.tags.join(", ")),
+        ("Location", event.location.clone()),
+        ("URL", event.url.clone()),
+        ("Reminders", event.remind.join(", ")),
+    ];
+    for (name, value) in fields.iter().filter(|(_, value)| !value.is_empty()) {
+        println!("{:<13}{}", format!("{}:", name), value);
+    }
+    for (key, value) in event.meta.iter() {
+        println!("{:<13}{}", format!("{}:", key), value);
+    }
+    if !event.notes.is_empty() {
+        println!("Notes:");
+        for line in event.notes.lines() {
+            println!("    {}", line);
+        }
+    }
+}
+
+/// Replace the {name} placeholders of the template with the values, unknown ones are kept
+fn fill_template(template: &str, values: &[(&str, &str)]) -> String {
+    let mut filled = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        filled.push_str(&rest[..start]);
+        let placeholder = &rest[start..];
+        let value = placeholder.find('}').and_then(|end| {
+            let name = &placeholder[1..end];
+            values
+                .iter()
+                .find(|(key, _)| *key == name)
+                .map(|(_, value)| (*value, end))
+        });
+        match value {
+            Some((value, end)) => {
+                filled.push_str(value);
+                rest = &placeholder[end + 1..];
+            }
+            None => {
+                filled.push('{');
+                rest = &placeholder[1..];
+            }
+        }
+    }
+    filled.push_str(rest);
+    filled
 }
 
-/// Select the correct string to print for the delta
+/// Select the correct string to print for the delta, in the language of the config
 fn format_delta(delta: &i64) -> String {
-    if delta < &0 {
-        if delta == &-1 {
-            return "yesterday".to_string();
-        } else {
-            format!("{} days ago", delta.abs())
+    config::get().locale.delta(*delta)
+}
+
+/// The delta from today to the date for printing, in working days with --business-days
+/// or with the anniversary for events over a year ago with --anniversaries
+fn format_delta_to(date: NaiveDate, today: NaiveDate) -> String {
+    if anniversary::enabled() {
+        if let Some(delta) = anniversary::delta(date, today) {
+            return delta;
         }
-    } else if delta > &0 {
-        format!("in {} days", delta)
+    }
+    if business_days::enabled() && date != today {
+        config::get()
+            .locale
+            .working_delta(business_days::working_days_until(today, date))
     } else {
-        "today".to_string()
+        format_delta(&(date - today).num_days())
     }
 }
 
-/// Read the events.csv file and return event vector, events and tmp paths
-fn read_csv_file() -> Result<CsvData, Error> {
+/// The delta of the event from today in the precision of its date
+fn event_delta(event: &Event, today: NaiveDate) -> String {
+    event
+        .precision
+        .delta(event.date, today)
+        .unwrap_or_else(|| format_delta_to(event.date, today))
+}
+
+/// The home directory on Linux and Windows
+fn home_directory() -> PathBuf {
     // Check if user is on Windows or Linux
-    let home_directory_string = match env::var("HOME") {
-        Ok(home_string) => home_string,
+    match env::var("HOME") {
+        Ok(home_string) => PathBuf::from(home_string),
         // If not on Linux, check if on Windows
         Err(_) => match env::var("USERPROFILE") {
-            Ok(user_profile_string) => user_profile_string,
+            Ok(user_profile_string) => PathBuf::from(user_profile_string),
             Err(_) => {
                 eprintln!("Unable to determine home directory");
                 process::exit(1);
             }
         },
-    };
+    }
+}
 
-    // Path for events.csv file
-    let mut days_path = PathBuf::from(home_directory_string);
-    days_path.push(".days");
+/// Read the events.csv file and return event vector, events and tmp paths
+fn read_csv_file() -> Result<CsvData, Error> {
+    // Path for events.csv file, ~/.days/events.csv unless set in the config
+    let events_path = config::get().data_file.clone();
+    let days_path = events_path.parent().unwrap_or(&events_path).to_path_buf();
 
-    if !days_path.exists() {
+    if !days_path.as_os_str().is_empty() && !days_path.exists() {
         println!("{} does not exist, please create it", days_path.display());
         process::exit(1);
     }
 
-    // Paths to events.csv and events.csv.tmp
-    let events_path = days_path.join("events.csv");
-    let temp_path = days_path.join("events.csv.tmp");
+    // Path to events.csv.tmp next to events.csv
+    let mut temp_name = events_path.clone().into_os_string();
+    temp_name.push(".tmp");
+    let temp_path = PathBuf::from(temp_name);
 
+    let events = read_events(&events_path)?;
+    if events.is_empty() {
+        println!("No events found");
+        process::exit(0);
+    }
+
+    // Return the paths and events
+    Ok(CsvData {
+        events_path,
+        temp_path,
+        events,
+    })
+}
+
+/// Read the events from the csv file, rows with bad dates are left out
+fn read_events(events_path: &PathBuf) -> Result<Vec<Event>, Error> {
     // Vector for events
     let mut events = Vec::new();
-    let csv = std::fs::read_to_string(&events_path)?;
+    let csv = std::fs::read_to_string(events_path)?;
 
-    // Reader options
+    // Reader options, the columns after the description are optional
     let mut rdr = csv::ReaderBuilder::new()
         .has_headers(true)
+        .flexible(true)
         .delimiter(b',')
         .from_reader(csv.as_bytes());
 
-    // Read the csv file
-    for result in rdr.records() {
+    // Read the csv file, the ids are the positions of the rows
+    for (i, result) in rdr.records().enumerate() {
         let record = result?;
-        let date_str = &record[0];
-        let category = &record[1];
-        let description = &record[2];
-
-        let date = NaiveDate::parse_from_str(date_str, "%Y-%m-%d");
 
         // Check if date is valid and push to events vector
-        if let Ok(date) = date {
-            events.push(Event {
-                date,
-                category: category.to_owned(),
-                description: description.to_owned(),
-            });
-        } else {
-            eprintln!("bad date: {}", date_str);
+        match record_to_event(&record, i + 1) {
+            Some(event) => events.push(event),
+            None => eprintln!("bad date: {}", &record[0]),
         }
     }
+    Ok(events)
+}
 
-    if events.is_empty() {
-        println!("No events found");
-        process::exit(0);
+/// Ask whether to delete the event if confirm_delete is set in the config
+fn confirm_delete(event: &Event) -> bool {
+    if config::get().confirm_delete {
+        print!(
+            "Delete event {}: {} ({})? [y/N] ",
+            event.precision.format(event.date),
+            event.description,
+            event.category
+        );
+        std::io::stdout().flush().unwrap();
+        let mut answer = String::new();
+        std::io::stdin().read_line(&mut answer).unwrap_or(0);
+        if !answer.trim().eq_ignore_ascii_case("y") {
+            println!(
+                "Skipped event {}: {}",
+                event.precision.format(event.date),
+                event.description
+            );
+            return false;
+        }
     }
+    true
+}
 
-    // Return the paths and events
-    Ok(CsvData {
-        events_path,
-        temp_path,
-        events,
-    })
+/// Delete the event from events.csv, returns whether it succeeded
+fn delete_an_event(events_path: &PathBuf, temp_path: &PathBuf, event: &Event) -> bool {
+    // Leave out the rows that are the same as the given event
+    let record = event_to_record(event);
+    match rewrite_events(events_path, temp_path, |e| {
+        (event_to_record(e) != record).then(|| e.clone())
+    }) {
+        Ok(_) => {
+            println!(
+                "Successfully deleted event {}: {} ({})",
+                event.precision.format(event.date),
+                event.description,
+                event.category
+            );
+            true
+        }
+        Err(e) => {
+            eprintln!("Error writing to file: {}", e);
+            false
+        }
+    }
 }
 
-fn delete_an_event(
+/// Rewrite events.csv row by row. The change gets each event and returns the event
+/// to write in its place, None leaves it out. Rows with bad dates are kept as they are.
+/// Notes can span several lines, so the rows are read as csv records instead of lines
+fn rewrite_events(
     events_path: &PathBuf,
     temp_path: &PathBuf,
-    line_to_delete: String,
-    event: &Event,
-) {
-    match File::open(&events_path) {
-        Ok(file) => {
-            let reader = BufReader::new(file);
-            // Open the temporary file for writing
-            match OpenOptions::new().write(true).create(true).open(&temp_path) {
-                Ok(file) => {
-                    let mut writer = BufWriter::new(file);
-
-                    // Add lines to new file that don't contain given event
-                    for line in reader.lines() {
-                        if let Ok(line) = line {
-                            if !line.contains(line_to_delete.as_str()) {
-                                writeln!(writer, "{}", line).unwrap();
-                            }
-                        }
-                    }
-
-                    // Flush the buffer to write the changes to disk
-                    writer.flush().unwrap();
-
-                    // Rename temporary events.csv.tmp to overwrite the original file
-                    std::fs::rename(&temp_path, &events_path).unwrap();
-                    println!(
-                        "Successfully deleted event {}: {} ({})",
-                        event.date, event.description, event.category
-                    );
-                }
-                Err(e) => {
-                    eprintln!("Error opening file: {}", e);
+    mut change: impl FnMut(&Event) -> Option<Event>,
+) -> Result<(), Error> {
+    let csv = std::fs::read_to_string(events_path)?;
+    let mut rdr = csv::ReaderBuilder::new()
+        .has_headers(true)
+        .flexible(true)
+        .from_reader(csv.as_bytes());
+    let file = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(temp_path)?;
+    let mut writer = csv::WriterBuilder::new()
+        .flexible(true)
+        .terminator(csv::Terminator::Any(b'\n'))
+        .from_writer(BufWriter::new(file));
+
+    writer.write_record(rdr.headers()?)?;
+    for (i, result) in rdr.records().enumerate() {
+        let record = result?;
+        match record_to_event(&record, i + 1) {
+            Some(event) => {
+                if let Some(changed) = change(&event) {
+                    writer.write_record(event_to_record(&changed))?;
                 }
             }
+            None => writer.write_record(&record)?,
         }
-        Err(e) => {
-            eprintln!("Error opening file: {}", e);
-        }
     }
+
+    // Flush the buffer and overwrite the original file
+    writer.flush()?;
+    drop(writer);
+    std::fs::rename(temp_path, events_path)?;
+    Ok(())
+}
+
+/// The event from a row of events.csv:
+/// date,category,description,tags,notes,location,url,meta,remind
+/// None if the date is bad
+fn record_to_event(record: &csv::StringRecord, id: usize) -> Option<Event> {
+    let field = |i: usize| record.get(i).unwrap_or("").to_string();
+    let (date, precision) = precision::parse(&field(0), date_expr::current_date())?;
+
+    let mut event = Event::new(date, field(1), field(2));
+    event.precision = precision;
+    event.tags = separate_tags(&field(3));
+    event.notes = field(4);
+    event.location = field(5);
+    event.url = field(6);
+    event.meta = separate_tags(&field(7))
+        .iter()
+        .filter_map(|pair| parse_meta(pair))
+        .collect();
+    event.remind = separate_tags(&field(8));
+    event.id = id;
+    Some(event)
+}
+
+/// The columns of the event in events.csv, the empty columns at the end are left out
+fn event_to_record(event: &Event) -> Vec<String> {
+    let meta: Vec<String> = event
+        .meta
+        .iter()
+        .map(|(key, value)| format!("{}={}", key, value))
+        .collect();
+    let mut record = vec![
+        event.precision.format(event.date),
+        event.category.clone(),
+        event.description.clone(),
+        event.tags.join(";"),
+        event.notes.clone(),
+        event.location.clone(),
+        event.url.clone(),
+        meta.join(";"),
+        event.remind.join(";"),
+    ];
+    while record.len() > 3 && record.last().is_some_and(|field| field.is_empty()) {
+        record.pop();
+    }
+    record
 }
 
 /// Format string to be like line in events.csv
-/// YYYY-MM-DD,category,description
+/// YYYY-MM-DD,category,description,tags,notes,location,url,meta,remind
+/// The empty columns at the end are left out, fields with commas, quotes or newlines are quoted
 fn csv_format_to_event(event: &Event) -> String {
-    format!(
-        "{},{},{}",
-        event.date.format("%Y-%m-%d"),
-        event.category,
-        event.description
-    )
+    let mut writer = csv::WriterBuilder::new()
+        .terminator(csv::Terminator::Any(b'\n'))
+        .from_writer(Vec::new());
+    writer.write_record(event_to_record(event)).unwrap();
+    let line = String::from_utf8(writer.into_inner().unwrap()).unwrap();
+    line.trim_end_matches('\n').to_string()
+}
+
+/// A key=value pair of the meta column, like room=B204
+fn parse_meta(pair: &str) -> Option<(String, String)> {
+    let (key, value) = pair.split_once('=')?;
+    let key = key.trim();
+    if key.is_empty() {
+        return None;
+    }
+    Some((key.to_string(), value.trim().to_string()))
+}
+
+/// Tags from the events.csv column, like rust;backend
+fn separate_tags(tags: &str) -> Vec<String> {
+    tags.split(';')
+        .map(|tag| tag.trim().to_string())
+        .filter(|tag| !tag.is_empty())
+        .collect()
+}
+
+/// The command and its arguments, like notify-send -u critical. Empty commands aren't allowed
+fn separate_command(command: &str) -> Vec<String> {
+    let command: Vec<String> = command.split_whitespace().map(|s| s.to_string()).collect();
+    if command.is_empty() {
+        eprintln!("Empty command given");
+        process::exit(1);
+    }
+    command
 }
 
 /// Removes commas from a string and makes it into a vector
-fn separate_args_to_vector(args: &String) -> Vec<String> {
+fn separate_args_to_vector(args: &str) -> Vec<String> {
     let separated_args: Vec<String> = args.split(",").map(|s| s.to_string()).collect();
     separated_args
 }
@@ -198,14 +543,53 @@ fn separate_args_to_vector(args: &String) -> Vec<String> {
 // Needed to surpress warnings for paths
 #[allow(unused_assignments)]
 fn main() {
-    // Current time
-    let now = chrono::Utc::now().naive_utc().date();
+    // Current date
+    let now = date_expr::current_date();
     // Arguments to vector
-    let args: Vec<String> = env::args().collect();
+    let mut args: Vec<String> = env::args().collect();
+    // Run the default command from the config if no arguments are given
+    if args.len() == 1 {
+        args.extend(config::get().default_command.iter().cloned());
+    }
+    // --color can be given with any command
+    let color_mode = colors::take_color_option(&mut args).unwrap_or(config::get().color);
+    colors::init(color_mode);
+
+    // The config command works without events.csv
+    let arg_config = "config";
+    if args.len() > 1 && args[1] == arg_config {
+        config::run_config_command(&args[2..]);
+        process::exit(0);
+    }
+    // The prompt command is run often, so it has its own cache and doesn't read events.csv every time
+    let arg_prompt = "prompt";
+    if args.len() > 1 && args[1] == arg_prompt {
+        prompt::run_prompt_command(&args[2..], now);
+        process::exit(0);
+    }
+    // The date calculator doesn't need the events
+    let arg_diff = "diff";
+    let arg_calc = "calc";
+    if args.len() > 1 && args[1] == arg_diff {
+        date_calc::run_diff_command(&args[2..], now);
+        process::exit(0);
+    }
+    if args.len() > 1 && args[1] == arg_calc {
+        date_calc::run_calc_command(&args[2..], now);
+        process::exit(0);
+    }
+    // The bar command can keep running and read events.csv again when it changes
+    let arg_bar = "bar";
+    if args.len() > 1 && args[1] == arg_bar {
+        bar::run_bar_command(&args[2..]);
+        process::exit(0);
+    }
     //println!("The arguments are: {:?} ja pituus {}", args, args.len());
 
     // Counter for found events
     let mut counter = 0;
+    // Print only the counter, set by list --count
+    let mut count_only = false;
     // Paths
     let mut events_path = PathBuf::new();
     let mut temp_path = PathBuf::new();
@@ -217,6 +601,7 @@ fn main() {
             events_path = csv_data.events_path;
             temp_path = csv_data.temp_path;
             events_vector = csv_data.events;
+            business_days::set_days_off(&events_vector);
         }
         Err(e) => {
             eprintln!("Error reading events.csv file: {}", e);
@@ -226,22 +611,46 @@ fn main() {
 
     // Arguments to compare args to. For listing, adding and deleting events
     let arg_list = "list";
-    let arg_today = "--today";
-    let arg_before_date = "--before-date";
-    let arg_after_date = "--after-date";
     let arg_date = "--date";
-    let arg_categories = "--categories";
-    let arg_exclude = "--exclude";
-    let arg_no_category = "--no-category";
     let arg_add = "add";
     let arg_category = "--category";
     let arg_description = "--description";
+    let arg_tag = "--tag";
+    let arg_notes = "--notes";
+    let arg_location = "--location";
+    let arg_url = "--url";
+    let arg_meta = "--meta";
+    let arg_ids = "--ids";
+    let arg_offset = "--offset";
+    let arg_show = "show";
+    let arg_business_days = "--business-days";
+    let arg_anniversaries = "--anniversaries";
+    let arg_on_this_day = "onthisday";
+    let arg_remind = "--remind";
+    let arg_remind_command = "remind";
+    let arg_exec = "--exec";
     let mut category = "";
     let mut description = "";
     let arg_delete = "delete";
     let arg_dry_run = "--dry-run";
     let arg_all = "--all";
     let arg_between = "--between";
+    let arg_search = "--search";
+    let arg_shift = "shift";
+    let arg_by = "--by";
+    let arg_stats = "stats";
+    let arg_json = "--json";
+    let arg_cal = "cal";
+    let arg_month = "--month";
+    let arg_year = "--year";
+    let arg_legend = "--legend";
+    let arg_agenda = "agenda";
+    let arg_next = "--next";
+    let arg_past = "--past";
+    let arg_limit = "--limit";
+    let arg_next_event = "next";
+    let arg_count = "--count";
+    let arg_categories_command = "categories";
 
     if args.len() == 1 {
         println!("No arguments entered. Use --help for help.");
@@ -261,123 +670,151 @@ fn main() {
             if args[2] == arg_delete {
                 help_prints::help_delete();
             }
-        } else {
-            println!("Available help commands are: list, add, delete. Example: 'days --help list'");
-        }
-    }
-
-    // Arguments starting with list
-    if args.len() > 1 && args[1] == arg_list {
-        // List all events
-        if args.len() == 2 {
-            for e in events_vector.iter() {
-                print_day_format(&e);
-                counter += 1;
+            if args[2] == arg_shift {
+                help_prints::help_shift();
             }
-        }
-
-        // List events by today
-        if args.len() > 2 && args[2] == arg_today {
-            for e in events_vector.iter() {
-                if e.date == now {
-                    print_day_format(&e);
-                    counter += 1;
-                }
+            if args[2] == arg_stats {
+                help_prints::help_stats();
             }
-        }
-
-        // List events by before, after or both
-        if args.len() > 2
-            && (args[2] == arg_before_date || args[2] == arg_after_date || args[2] == arg_date)
-        {
-            if args.len() < 4 {
-                eprintln!("No date given");
-                process::exit(1);
+            if args[2] == arg_cal {
+                help_prints::help_cal();
             }
-
-            let mut before = false;
-            let mut after = false;
-            let mut on_this_date = false;
-            let mut date1 = NaiveDate::parse_from_str(&args[3], "%Y-%m-%d");
-            let mut date2 = NaiveDate::parse_from_str(&args[3], "%Y-%m-%d");
-
-            if args[2] == arg_before_date {
-                before = true;
+            if args[2] == arg_agenda {
+                help_prints::help_agenda();
             }
-
-            if args[2] == arg_after_date {
-                after = true;
+            if args[2] == arg_next_event {
+                help_prints::help_next();
             }
-
-            if args.len() > 4 && args[2] == arg_before_date && args[4] == arg_after_date {
-                before = true;
-                after = true;
-                date1 = NaiveDate::parse_from_str(&args[3], "%Y-%m-%d");
-                date2 = NaiveDate::parse_from_str(&args[5], "%Y-%m-%d");
+            if args[2] == arg_config {
+                help_prints::help_config();
             }
-
-            if args[2] == arg_date {
-                on_this_date = true;
+            if args[2] == arg_categories_command {
+                help_prints::help_categories();
+            }
+            if args[2] == arg_show {
+                help_prints::help_show();
+            }
+            if args[2] == arg_on_this_day {
+                help_prints::help_on_this_day();
+            }
+            if args[2] == arg_remind_command {
+                help_prints::help_remind();
+            }
+            if args[2] == arg_prompt {
+                help_prints::help_prompt();
+            }
+            if args[2] == arg_bar {
+                help_prints::help_bar();
+            }
+            if args[2] == arg_diff {
+                help_prints::help_diff();
             }
+            if args[2] == arg_calc {
+                help_prints::help_calc();
+            }
+        } else {
+            println!(
+                "Available help commands are: list, add, delete, show, shift, stats, cal, agenda, next, onthisday, remind, prompt, bar, diff, calc, categories, config. Example: 'days --help list'"
+            );
+            println!("Every command takes --color auto|always|never, NO_COLOR=1 turns colors off");
+        }
+    }
 
-            for e in events_vector.iter() {
-                if before {
-                    if e.date < date1.unwrap() {
-                        print_day_format(&e);
-                        counter += 1;
-                    }
-                }
-                if after {
-                    if e.date > date2.unwrap() {
-                        print_day_format(&e);
-                        counter += 1;
+    // Arguments starting with list
+    if args.len() > 1 && args[1] == arg_list {
+        // --ids, --business-days, --count, --limit and --offset can be anywhere,
+        // the rest of the arguments are list options
+        let mut ids = false;
+        let mut limit = None;
+        let mut offset = 0;
+        let mut filter_options: Vec<String> = Vec::new();
+        let mut i = 2;
+        while i < args.len() {
+            if args[i] == arg_limit || args[i] == arg_offset {
+                let value = match args.get(i + 1).map(|value| value.parse::<usize>()) {
+                    Some(Ok(value)) => value,
+                    Some(Err(_)) => {
+                        eprintln!("Bad number given for {}: {}", args[i], args[i + 1]);
+                        process::exit(1);
                     }
-                }
-                if on_this_date {
-                    if e.date == date1.unwrap() {
-                        print_day_format(&e);
-                        counter += 1;
+                    None => {
+                        eprintln!("No value given for {}", args[i]);
+                        process::exit(1);
                     }
+                };
+                if args[i] == arg_limit {
+                    limit = Some(value);
+                } else {
+                    offset = value;
                 }
+                i += 2;
+                continue;
             }
+            if args[i] == arg_ids {
+                ids = true;
+            } else if args[i] == arg_count {
+                count_only = true;
+            } else if args[i] == arg_business_days {
+                business_days::enable();
+            } else {
+                filter_options.push(args[i].clone());
+            }
+            i += 1;
+        }
+        let (search, _) = Search::from_options(&filter_options);
+        let (first_year, last_year) = filters::shown_years(&filter_options, now);
+        let calendar_events = holidays::with_holidays(&events_vector, first_year, last_year);
+        let mut selected = filters::filter_events(&filter_options, &calendar_events, now);
+        // The nearest anniversaries first
+        if filter_options.iter().any(|a| a == arg_anniversaries) {
+            anniversary::enable();
+            selected.sort_by_key(|e| anniversary::next(e.date, now).map(|(date, _)| date));
         }
+        let shown = selected
+            .into_iter()
+            .skip(offset)
+            .take(limit.unwrap_or(usize::MAX));
 
-        // Categories, still inside list
-        // if argument after list is --categories
-        if args.len() > 2 && (args[2] == arg_categories || args[2] == arg_no_category) {
-            // Print events with no categories
-            if args[2] == arg_no_category {
-                for e in events_vector.iter() {
-                    if e.category == "" {
-                        print_day_format(&e);
-                        counter += 1;
-                    }
-                }
+        let mut lines = Vec::new();
+        for e in shown {
+            counter += 1;
+            if count_only {
+                continue;
+            }
+            let line = day_format(e, search.as_ref());
+            // Holidays aren't in events.csv, so they have no id
+            if ids && e.id == 0 {
+                lines.push(format!("{:<5}{}", "", line));
+            } else if ids {
+                lines.push(format!("#{:<4}{}", e.id, line));
             } else {
-                // Check if there are enough arguments
-                if args.len() < 4 {
-                    eprintln!("No category given");
-                    process::exit(1);
-                }
-
-                // Separate the categories into a vector
-                let arg_categories: Vec<String> = separate_args_to_vector(&args[3]);
-                // Check if user gave --exclude argument
-                let exclude: bool = args.len() > 4 && args[4] == arg_exclude;
+                lines.push(line);
+            }
+        }
+        pager::print_lines(&lines);
+    }
 
-                for event in events_vector.iter() {
-                    if exclude {
-                        if !arg_categories.contains(&event.category) {
-                            print_day_format(&event);
-                            counter += 1;
-                        }
-                    } else {
-                        if arg_categories.contains(&event.category) {
-                            print_day_format(&event);
-                            counter += 1;
-                        }
-                    }
-                }
+    // Arguments starting with show
+    if args.len() > 1 && args[1] == arg_show {
+        if args.len() < 3 {
+            eprintln!("No id given, the ids are shown with 'days list --ids'");
+            process::exit(1);
+        }
+        let id = match args[2].trim_start_matches('#').parse::<usize>() {
+            Ok(id) => id,
+            Err(_) => {
+                eprintln!("Bad id given: {}", args[2]);
+                process::exit(1);
+            }
+        };
+        match events_vector.iter().find(|e| e.id == id) {
+            Some(event) => {
+                print_event_details(event);
+                counter += 1;
+            }
+            None => {
+                eprintln!("No event with id {}", id);
+                process::exit(1);
             }
         }
     }
@@ -389,17 +826,23 @@ fn main() {
             process::exit(1);
         }
 
-        let mut date = NaiveDate::parse_from_str(&args[3], "%Y-%m-%d");
+        let mut date = now;
+        let mut precision = Precision::Day;
 
-        if args[2] != arg_date {
-            date = Ok(now);
-        }
-
-        if date.is_err() {
-            eprintln!("Bad date given");
-            process::exit(1);
+        if args[2] == arg_date {
+            // Partial dates like 2023-05, 1985 and --05-10 first, then date expressions
+            (date, precision) = match precision::parse(&args[3], now) {
+                Some(parsed) => parsed,
+                None => (date_expr::parse_date_or_exit(&args[3], now), Precision::Day),
+            };
         }
 
+        let mut tags = Vec::new();
+        let mut notes = String::new();
+        let mut location = String::new();
+        let mut url = String::new();
+        let mut meta = Vec::new();
+        let mut remind_leads = Vec::new();
         for i in 2..args.len() {
             if args[i] == arg_category {
                 category = &args[i + 1];
@@ -407,9 +850,55 @@ fn main() {
             if args[i] == arg_description {
                 description = &args[i + 1];
             }
+            // --tag rust,backend is the same as --tag rust --tag backend
+            if args[i] == arg_tag && i + 1 < args.len() {
+                tags.extend(separate_tags(&args[i + 1].replace(',', ";")));
+            }
+            // --remind 7d,1d, reminders 7 days and 1 day before
+            if args[i] == arg_remind && i + 1 < args.len() {
+                for lead in separate_tags(&args[i + 1].replace(',', ";")) {
+                    if remind::parse_lead_time(&lead).is_none() {
+                        eprintln!("Bad reminder given: {}, use lead times like 7d or 2w", lead);
+                        process::exit(1);
+                    }
+                    remind_leads.push(lead);
+                }
+            }
+            if args[i] == arg_notes && i + 1 < args.len() {
+                notes = args[i + 1].clone();
+            }
+            if args[i] == arg_location && i + 1 < args.len() {
+                location = args[i + 1].clone();
+            }
+            if args[i] == arg_url && i + 1 < args.len() {
+                url = args[i + 1].clone();
+            }
+            // --meta room=B204, can be given many times
+            if args[i] == arg_meta && i + 1 < args.len() {
+                match parse_meta(&args[i + 1]) {
+                    Some(pair) if !pair.1.contains(';') => meta.push(pair),
+                    _ => {
+                        eprintln!("Bad meta given: {}, use key=value", args[i + 1]);
+                        process::exit(1);
+                    }
+                }
+            }
         }
-        let event = Event::new(date.unwrap(), category.to_string(), description.to_string());
+        // Use the registered name if the category is an alias
+        let category = match categories::get().find(category) {
+            Some(registered) => registered.name.clone(),
+            None => category.to_string(),
+        };
+        let mut event = Event::new(date, category, description.to_string());
+        event.precision = precision;
+        event.tags = tags;
+        event.notes = notes;
+        event.location = location;
+        event.url = url;
+        event.meta = meta;
+        event.remind = remind_leads;
         let event_formatted = csv_format_to_event(&event);
+        hooks::run_pre("add", &[&event], &[]);
 
         match OpenOptions::new().append(true).open(&events_path) {
             Ok(file) => {
@@ -419,9 +908,14 @@ fn main() {
                     Ok(_) => {
                         println!(
                             "Successfully added event {}: {} ({})",
-                            event.date, event.description, event.category
+                            event.precision.format(event.date),
+                            event.description,
+                            event.category
                         );
                         counter += 1;
+                        // The file is closed first so the hook sees the new event
+                        drop(writer);
+                        hooks::run_post("add", &[&event], &[]);
                     }
                     Err(e) => {
                         eprintln!("Error writing to file: {}", e);
@@ -442,6 +936,8 @@ fn main() {
         }
 
         let length = args.len() - 1;
+        // The events to delete, they are deleted after the hooks have been run
+        let mut to_delete: Vec<&Event> = Vec::new();
 
         // If --description is given as first argument after delete
         if args[2] == arg_description || args[2] == arg_category {
@@ -451,32 +947,62 @@ fn main() {
                 // Check if the given arguments matches, if user gave --description
                 // search for matching descriptions and ignore categories
                 if (is_description && event.description.starts_with(&args[3]))
-                    || (!is_description && event.category == args[3])
+                    || (!is_description && categories::get().same(&event.category, &args[3]))
                 {
                     // Check for dry-run
                     if args.len() > 4 && args[length] == arg_dry_run {
                         println!(
                             "{}: {} ({}) would have been deleted without dry-run",
-                            event.date, event.category, event.description
+                            event.precision.format(event.date),
+                            event.category,
+                            event.description
                         );
                     // Delete events for real if dry-run not given
                     } else {
-                        let event_formatted = csv_format_to_event(&event);
-                        delete_an_event(&events_path, &temp_path, event_formatted, &event);
+                        to_delete.push(event);
                     }
                     counter += 1;
                 }
             }
         }
 
-        // If --date is given as first argument after delete
-        if args.len() > 3 && args[2] == arg_date {
-            let date = NaiveDate::parse_from_str(&args[3], "%Y-%m-%d");
+        // If --search is given, delete events with matching descriptions
+        if args[2] == arg_search {
+            let search = match Search::from_options(&args[2..]) {
+                (Some(search), _) => search,
+                (None, _) => process::exit(1),
+            };
 
-            if date.is_err() {
-                eprintln!("Bad date given");
-                process::exit(1);
+            for event in events_vector.iter() {
+                if search.is_match(&event.description) {
+                    // Check for dry-run
+                    if args[length] == arg_dry_run {
+                        println!(
+                            "{}: {} ({}) would have been deleted without dry-run",
+                            event.precision.format(event.date),
+                            event.description,
+                            event.category
+                        );
+                    // Delete events for real if dry-run not given
+                    } else {
+                        to_delete.push(event);
+                    }
+                    counter += 1;
+                }
             }
+        }
+
+        // If --date is given as first argument after delete
+        if args.len() > 3 && args[2] == arg_date {
+            // Events match by the date like it's stored, so --date 2023-05-12 doesn't delete
+            // a yearly --05-12 and partial dates like 2023-05 or --05-12 can be given
+            let stored_date = match precision::parse(&args[3], now) {
+                Some((date, precision)) => precision.format(date),
+                None => date_expr::parse_date_or_exit(&args[3], now)
+                    .format("%Y-%m-%d")
+                    .to_string(),
+            };
+            let on_date = |event: &Event| event.precision.format(event.date) == stored_date;
 
             let has_category: bool = args.len() > 5 && args[4] == arg_category;
             let has_description: bool = args.len() > 6 && args[4] == arg_description;
@@ -496,61 +1022,62 @@ fn main() {
                 // If category is given, find events with given date and category
                 if has_category {
                     // If description is not given, find events with given date and category
-                    if !has_description {
-                        if event.date == date.unwrap() && event.category == category {
-                            // Check for dry-run
-                            if args.len() > 4 && args[length] == arg_dry_run {
-                                println!(
-                                    "{}: {} ({}) would have been deleted without dry-run",
-                                    event.date, event.description, event.category
-                                );
-                            // Delete events for real if dry-run not given
-                            } else {
-                                let event_formatted = csv_format_to_event(&event);
-                                delete_an_event(&events_path, &temp_path, event_formatted, &event);
-                            }
-                            counter += 1;
+                    if !has_description
+                        && on_date(event)
+                        && categories::get().same(&event.category, category)
+                    {
+                        // Check for dry-run
+                        if args.len() > 4 && args[length] == arg_dry_run {
+                            println!(
+                                "{}: {} ({}) would have been deleted without dry-run",
+                                event.precision.format(event.date),
+                                event.description,
+                                event.category
+                            );
+                        // Delete events for real if dry-run not given
+                        } else {
+                            to_delete.push(event);
                         }
+                        counter += 1;
                     }
                     // If description is given, find events with given date, category and description
-                    if has_description {
-                        if event.date == date.unwrap()
-                            && event.category == category
-                            && event.description.starts_with(description)
-                        {
-                            // Check for dry-run
-                            if args.len() > 4 && args[length] == arg_dry_run {
-                                println!(
-                                    "{}: {} ({}) would have been deleted without dry-run",
-                                    event.date, event.description, event.category
-                                );
-                            // Delete events for real if dry-run not given
-                            } else {
-                                let event_formatted = csv_format_to_event(&event);
-                                delete_an_event(&events_path, &temp_path, event_formatted, event);
-                            }
-                            counter += 1;
-                        }
-                    }
-                }
-
-                // If category is not given, find events just with given date
-                if !has_category {
-                    if event.date == date.unwrap() {
+                    if has_description
+                        && on_date(event)
+                        && categories::get().same(&event.category, category)
+                        && event.description.starts_with(description)
+                    {
                         // Check for dry-run
                         if args.len() > 4 && args[length] == arg_dry_run {
                             println!(
                                 "{}: {} ({}) would have been deleted without dry-run",
-                                event.date, event.description, event.category
+                                event.precision.format(event.date),
+                                event.description,
+                                event.category
                             );
                         // Delete events for real if dry-run not given
                         } else {
-                            let event_formatted = csv_format_to_event(&event);
-                            delete_an_event(&events_path, &temp_path, event_formatted, &event);
+                            to_delete.push(event);
                         }
                         counter += 1;
                     }
                 }
+
+                // If category is not given, find events just with given date
+                if !has_category && on_date(event) {
+                    // Check for dry-run
+                    if args.len() > 4 && args[length] == arg_dry_run {
+                        println!(
+                            "{}: {} ({}) would have been deleted without dry-run",
+                            event.precision.format(event.date),
+                            event.description,
+                            event.category
+                        );
+                    // Delete events for real if dry-run not given
+                    } else {
+                        to_delete.push(event);
+                    }
+                    counter += 1;
+                }
             }
         }
 
@@ -560,52 +1087,448 @@ fn main() {
                 for event in events_vector.iter() {
                     println!(
                         "{}: {} ({}) would have been deleted without dry-run",
-                        event.date, event.description, event.category
+                        event.precision.format(event.date),
+                        event.description,
+                        event.category
                     );
                 }
             }
             // Delete events for real if dry-run not given
             if args.len() == 3 {
                 for event in events_vector.iter() {
-                    let event_formatted = csv_format_to_event(&event);
-                    // submits all lines and deletes them
-                    delete_an_event(&events_path, &temp_path, event_formatted, &event);
+                    // submits all events and deletes them
+                    to_delete.push(event);
                 }
             }
             counter += 1;
         }
 
         if args[2] == arg_between {
-            if args.len() != 5 && args.len() != 6 {
-                eprintln!("No dates given or wrong formatting.\n");
-                process::exit(1);
+            let range = filters::parse_date_range(&args[2..], now);
+
+            for event in events_vector.iter() {
+                if filters::in_range(event, range) {
+                    // Check for dry-run
+                    if args[length] == arg_dry_run {
+                        println!(
+                            "{}: {} ({}) would have been deleted without dry-run",
+                            event.precision.format(event.date),
+                            event.description,
+                            event.category
+                        );
+                    // Delete events for real if dry-run not given
+                    } else {
+                        to_delete.push(event);
+                    }
+                    counter += 1;
+                }
             }
+        }
 
-            let date1 = NaiveDate::parse_from_str(&args[3], "%Y-%m-%d");
-            let date2 = NaiveDate::parse_from_str(&args[4], "%Y-%m-%d");
+        // Ask for each event first, so the hooks only get the events that are deleted
+        let to_delete: Vec<&Event> = to_delete
+            .into_iter()
+            .filter(|e| confirm_delete(e))
+            .collect();
+        if !to_delete.is_empty() {
+            hooks::run_pre("delete", &to_delete, &[]);
+            let deleted: Vec<&Event> = to_delete
+                .into_iter()
+                .filter(|e| delete_an_event(&events_path, &temp_path, e))
+                .collect();
+            if !deleted.is_empty() {
+                hooks::run_post("delete", &deleted, &[]);
+            }
+        }
+    }
 
-            if date1.is_err() || date2.is_err() {
-                eprintln!("Bad date given");
+    // Arguments starting with shift
+    if args.len() > 1 && args[1] == arg_shift {
+        // Pick the offset and --dry-run, the rest of the arguments are list options
+        let mut offset = None;
+        let mut dry_run = false;
+        let mut filter_options: Vec<String> = Vec::new();
+        let mut i = 2;
+        while i < args.len() {
+            if args[i] == arg_by {
+                if i + 1 >= args.len() {
+                    eprintln!("No offset given");
+                    process::exit(1);
+                }
+                offset = DateOffset::parse(&args[i + 1]);
+                if offset.is_none() {
+                    eprintln!("Bad offset given: {}", args[i + 1]);
+                    process::exit(1);
+                }
+                i += 2;
+                continue;
+            }
+            if args[i] == arg_dry_run {
+                dry_run = true;
+            } else {
+                filter_options.push(args[i].clone());
+            }
+            i += 1;
+        }
+
+        let offset = match offset {
+            Some(offset) => offset,
+            None => {
+                eprintln!("No offset given, for example --by +14d");
                 process::exit(1);
             }
+        };
 
-            for event in events_vector {
-                if event.date >= date1.unwrap() && event.date <= date2.unwrap() {
-                    // Check for dry-run
-                    if args.len() > 5 && args[length] == arg_dry_run {
+        // Shifting every event by accident would be hard to undo, so require a filter
+        if filter_options.is_empty() {
+            eprintln!("No events selected, use the list options like --categories school");
+            process::exit(1);
+        }
+
+        // Pairs of the original and the shifted event
+        let mut shifted_events = Vec::new();
+        // Months and years are selected when any of their days is, but days and weeks
+        // can't move them, so they're left out with a note
+        let (selected, unshiftable): (Vec<&Event>, Vec<&Event>) =
+            filters::filter_events(&filter_options, &events_vector, now)
+                .into_iter()
+                .partition(|e| e.precision.can_shift_by(offset));
+        for event in unshiftable.iter() {
+            eprintln!(
+                "Left out {}: {} ({}), it can't be shifted by {}",
+                event.precision.format(event.date),
+                event.description,
+                event.category,
+                offset
+            );
+        }
+        if selected.is_empty() && !unshiftable.is_empty() {
+            eprintln!("Nothing to shift: months are shifted by months or years, years by years and year-less dates not by years");
+            process::exit(1);
+        }
+        for event in selected {
+            match event.precision.shift(event.date, offset) {
+                Some((date, precision)) => {
+                    let shifted = Event {
+                        date,
+                        precision,
+                        ..(*event).clone()
+                    };
+                    shifted_events.push((event, shifted));
+                }
+                None => {
+                    eprintln!(
+                        "Date out of range when shifting {}",
+                        event.precision.format(event.date)
+                    );
+                    process::exit(1);
+                }
+            }
+        }
+
+        if dry_run {
+            for (event, shifted) in shifted_events.iter() {
+                println!("- {}", csv_format_to_event(event));
+                println!("+ {}", csv_format_to_event(shifted));
+            }
+        } else if !shifted_events.is_empty() {
+            // The rows are matched by their ids, events.csv hasn't changed since it was read
+            let change = |e: &Event| match shifted_events.iter().find(|(event, _)| event.id == e.id)
+            {
+                Some((_, shifted)) => Some(shifted.clone()),
+                None => Some(e.clone()),
+            };
+
+            // Shifting is an edit for the hooks
+            let previous: Vec<&Event> = shifted_events.iter().map(|(event, _)| *event).collect();
+            let shifted: Vec<&Event> = shifted_events.iter().map(|(_, shifted)| shifted).collect();
+            hooks::run_pre("edit", &shifted, &previous);
+
+            match rewrite_events(&events_path, &temp_path, change) {
+                Ok(_) => {
+                    for (event, shifted) in shifted_events.iter() {
                         println!(
-                            "{}: {} ({}) would have been deleted without dry-run",
-                            event.date, event.description, event.category
+                            "Successfully shifted event {} -> {}: {} ({})",
+                            event.precision.format(event.date),
+                            shifted.precision.format(shifted.date),
+                            event.description,
+                            event.category
                         );
-                    // Delete events for real if dry-run not given
+                    }
+                    hooks::run_post("edit", &shifted, &previous);
+                }
+                Err(e) => {
+                    eprintln!("Error writing to file: {}", e);
+                    process::exit(1);
+                }
+            }
+        }
+        counter += shifted_events.len();
+    }
+
+    // Arguments starting with stats
+    if args.len() > 1 && args[1] == arg_stats {
+        // --json can be anywhere, the rest of the arguments are list options
+        let json = args[2..].iter().any(|a| a == arg_json);
+        let filter_options: Vec<String> = args[2..]
+            .iter()
+            .filter(|a| *a != arg_json)
+            .cloned()
+            .collect();
+
+        let selected = filters::filter_events(&filter_options, &events_vector, now);
+        let stats = Stats::new(&selected);
+
+        if json {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&stats.to_json()).unwrap()
+            );
+            counter += 1;
+        } else if stats.total() > 0 {
+            stats.print_table();
+            counter += stats.total();
+        }
+    }
+
+    // Arguments starting with cal
+    if args.len() > 1 && args[1] == arg_cal {
+        // --legend can be anywhere, the rest of the arguments are list options
+        let legend = args[2..].iter().any(|a| a == arg_legend);
+        let filter_options: Vec<String> = args[2..]
+            .iter()
+            .filter(|a| *a != arg_legend)
+            .cloned()
+            .collect();
+        // The month or year to show, the filters check the values
+        let mut month = None;
+        let mut year = None;
+        for i in 2..args.len() - 1 {
+            if args[i] == arg_month {
+                month = date_expr::parse_month(&args[i + 1]);
+            }
+            if args[i] == arg_year {
+                year = date_expr::parse_year(&args[i + 1]);
+            }
+        }
+
+        // This year by default, like the calendar
+        let shown_years = [month, year]
+            .into_iter()
+            .flatten()
+            .map(|(first, _)| first.year());
+        let first_year = shown_years.clone().min().unwrap_or(now.year());
+        let last_year = shown_years.max().unwrap_or(now.year());
+        let calendar_events = holidays::with_holidays(&events_vector, first_year, last_year);
+        let mut selected = filters::filter_events(&filter_options, &calendar_events, now);
+        selected.sort_by_key(|e| e.date);
+
+        let color = colors::enabled();
+        match (month, year) {
+            (None, Some((first, _))) => cal::print_year(first.year(), &selected, now, color),
+            (Some((first, _)), _) => {
+                cal::print_month(first.year(), first.month(), &selected, now, color)
+            }
+            (None, None) => {
+                // Show only this month's events in the legend
+                selected.retain(|e| e.date.year() == now.year() && e.date.month() == now.month());
+                cal::print_month(now.year(), now.month(), &selected, now, color)
+            }
+        }
+
+        if legend && !selected.is_empty() {
+            println!();
+            for e in selected.iter() {
+                print_day_format(e, None);
+            }
+        }
+        counter += 1;
+    }
+
+    // Arguments starting with agenda
+    if args.len() > 1 && args[1] == arg_agenda {
+        // By default the next week, without past events
+        let mut next = DateOffset::Weeks(1);
+        let mut past = DateOffset::Days(0);
+        let mut limit = None;
+        let mut filter_options: Vec<String> = Vec::new();
+        let mut i = 2;
+        while i < args.len() {
+            if args[i] == arg_next || args[i] == arg_past || args[i] == arg_limit {
+                if i + 1 >= args.len() {
+                    eprintln!("No value given for {}", args[i]);
+                    process::exit(1);
+                }
+                let value = &args[i + 1];
+                if args[i] == arg_limit {
+                    match value.parse::<usize>() {
+                        Ok(value) => limit = Some(value),
+                        Err(_) => {
+                            eprintln!("Bad limit given: {}", value);
+                            process::exit(1);
+                        }
+                    }
+                } else {
+                    // The direction comes from the option, so the sign is not needed
+                    let offset = match DateOffset::parse(value.trim_start_matches(['+', '-'])) {
+                        Some(offset) => offset,
+                        None => {
+                            eprintln!("Bad offset given: {}, use for example 14d or 2w", value);
+                            process::exit(1);
+                        }
+                    };
+                    if args[i] == arg_next {
+                        next = offset;
                     } else {
-                        let event_formatted = csv_format_to_event(&event);
-                        delete_an_event(&events_path, &temp_path, event_formatted, &event);
+                        past = offset.negated();
                     }
-                    counter += 1;
                 }
+                i += 2;
+                continue;
+            }
+            if args[i] == arg_business_days {
+                business_days::enable();
+            } else {
+                filter_options.push(args[i].clone());
+            }
+            i += 1;
+        }
+
+        let (first, last) = match (past.apply(now), next.apply(now)) {
+            (Some(first), Some(last)) => (first, last),
+            _ => {
+                eprintln!("Date out of range");
+                process::exit(1);
+            }
+        };
+
+        let calendar_events = holidays::with_holidays(&events_vector, first.year(), last.year());
+        let mut selected = filters::filter_events(&filter_options, &calendar_events, now);
+        // Months and years have no day to be shown on
+        selected.retain(|e| e.precision.has_day() && e.date >= first && e.date <= last);
+        selected.sort_by_key(|e| e.date);
+        if let Some(limit) = limit {
+            selected.truncate(limit);
+        }
+
+        agenda::print_agenda(&selected, now);
+        counter += selected.len();
+    }
+
+    // Arguments starting with next
+    if args.len() > 1 && args[1] == arg_next_event {
+        let mut count = 1;
+        let mut next_category = None;
+        let mut filter_options: Vec<String> = Vec::new();
+        let mut i = 2;
+        while i < args.len() {
+            if args[i] == arg_count || args[i] == arg_category {
+                if i + 1 >= args.len() {
+                    eprintln!("No value given for {}", args[i]);
+                    process::exit(1);
+                }
+                if args[i] == arg_category {
+                    next_category = Some(args[i + 1].clone());
+                } else {
+                    match args[i + 1].parse::<usize>() {
+                        Ok(value) => count = value,
+                        Err(_) => {
+                            eprintln!("Bad count given: {}", args[i + 1]);
+                            process::exit(1);
+                        }
+                    }
+                }
+                i += 2;
+                continue;
+            }
+            filter_options.push(args[i].clone());
+            i += 1;
+        }
+
+        // Upcoming events, today included, nearest first
+        let mut selected = filters::filter_events(&filter_options, &events_vector, now);
+        // Months and years have no day to be next on
+        selected.retain(|e| e.precision.has_day() && e.date >= now);
+        if let Some(next_category) = next_category {
+            selected.retain(|e| categories::get().matches(&e.category, &next_category));
+        }
+        selected.sort_by_key(|e| e.date);
+        selected.truncate(count);
+
+        // Exit with an error so scripts can tell when there's nothing coming
+        if selected.is_empty() {
+            eprintln!("No upcoming events");
+            process::exit(1);
+        }
+
+        for e in selected.iter() {
+            print_day_format(e, None);
+        }
+        counter += selected.len();
+    }
+
+    // Arguments starting with onthisday
+    if args.len() > 1 && args[1] == arg_on_this_day {
+        let (mut month, mut day) = (now.month(), now.day());
+        if args.len() > 2 {
+            // --date MM-DD, checked against a leap year so 02-29 is allowed
+            let date = match (args[2] == arg_date, args.get(3)) {
+                (true, Some(value)) => {
+                    NaiveDate::parse_from_str(&format!("2000-{}", value), "%Y-%m-%d").ok()
+                }
+                _ => {
+                    eprintln!("Use --date MM-DD, like --date 12-31");
+                    process::exit(1);
+                }
+            };
+            match date {
+                Some(date) => (month, day) = (date.month(), date.day()),
+                None => {
+                    eprintln!("Bad date given: {}, use MM-DD like 12-31", args[3]);
+                    process::exit(1);
+                }
+            }
+        }
+        counter += anniversary::print_on_this_day(&events_vector, month, day, now);
+    }
+
+    // Arguments starting with remind, prints nothing when there are no reminders for cron
+    if args.len() > 1 && args[1] == arg_remind_command {
+        let mut exec = None;
+        let mut dry_run = false;
+        let mut i = 2;
+        while i < args.len() {
+            if args[i] == arg_exec {
+                if i + 1 >= args.len() {
+                    eprintln!("No command given for {}", arg_exec);
+                    process::exit(1);
+                }
+                exec = Some(separate_command(&args[i + 1]));
+                i += 2;
+                continue;
+            }
+            if args[i] == arg_dry_run {
+                dry_run = true;
+            } else {
+                eprintln!("Unknown option: {}", args[i]);
+                process::exit(1);
             }
+            i += 1;
         }
+        remind::remind(&events_vector, now, exec.as_deref(), dry_run);
+        process::exit(0);
+    }
+
+    // Arguments starting with categories
+    if args.len() > 1 && args[1] == arg_categories_command {
+        categories::get().print_categories(&events_vector);
+        counter += 1;
+    }
+
+    // With --count only the number of events is printed, for scripts
+    if count_only {
+        println!("{}", counter);
+        process::exit(0);
     }
 
     // If no events were printed, print this
@@ -613,5 +1536,124 @@ fn main() {
         println!("No events found");
     }
     // Empty line for readability in the command line
-    println!("");
+    println!();
+}
+
+/// A date for the tests, like date(2023, 5, 10)
+#[cfg(test)]
+fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The event of a line written by csv_format_to_event
+    fn read_line(line: &str) -> Event {
+        let mut rdr = csv::ReaderBuilder::new()
+            .has_headers(false)
+            .flexible(true)
+            .from_reader(line.as_bytes());
+        let record = rdr.records().next().unwrap().unwrap();
+        record_to_event(&record, 1).unwrap()
+    }
+
+    fn full_event() -> Event {
+        let mut event = Event::new(
+            date(2023, 5, 10),
+            "school".to_string(),
+            "Course, \"part 1\"".to_string(),
+        );
+        event.tags = vec!["rust".to_string(), "backend".to_string()];
+        event.notes = "First line\nSecond, with a comma".to_string();
+        event.location = "Room B204".to_string();
+        event.url = "https://example.com/?a=1,2".to_string();
+        event.meta = vec![
+            ("room".to_string(), "B204".to_string()),
+            ("seats".to_string(), "30".to_string()),
+        ];
+        event.remind = vec!["7d".to_string(), "1d".to_string()];
+        event
+    }
+
+    #[test]
+    fn leaves_out_empty_columns_at_the_end() {
+        let event = Event::new(
+            date(2023, 5, 10),
+            "school".to_string(),
+            "Course".to_string(),
+        );
+        assert_eq!(csv_format_to_event(&event), "2023-05-10,school,Course");
+
+        let mut event = event;
+        event.url = "https://example.com".to_string();
+        assert_eq!(
+            csv_format_to_event(&event),
+            "2023-05-10,school,Course,,,,https://example.com"
+        );
+    }
+
+    #[test]
+    fn writes_and_reads_every_column() {
+        let event = full_event();
+        let read = read_line(&csv_format_to_event(&event));
+        assert_eq!(event_to_record(&read), event_to_record(&event));
+        assert_eq!(read.description, event.description);
+        assert_eq!(read.notes, event.notes);
+        assert_eq!(read.tags, event.tags);
+        assert_eq!(read.meta, event.meta);
+        assert_eq!(read.remind, event.remind);
+    }
+
+    #[test]
+    fn keeps_partial_dates_like_they_are_stored() {
+        for stored in ["2023-05", "1985", "--05-12", "--02-29"] {
+            let read = read_line(&format!("{},family,Birthday", stored));
+            assert_eq!(
+                csv_format_to_event(&read),
+                format!("{},family,Birthday", stored)
+            );
+        }
+    }
+
+    #[test]
+    fn rewrites_only_the_changed_rows() {
+        let directory = env::temp_dir().join(format!("days-test-{}", process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        let events_path = directory.join("events.csv");
+        let temp_path = directory.join("events.csv.tmp");
+        let notes_row = csv_format_to_event(&full_event());
+        std::fs::write(
+            &events_path,
+            format!(
+                "date,category,description\n2023-05-09,school,Go\nnot a date,x,Kept\n{}\n2023-05-11,school,Delete me\n",
+                notes_row
+            ),
+        )
+        .unwrap();
+
+        rewrite_events(&events_path, &temp_path, |event| {
+            match event.description.as_str() {
+                "Delete me" => None,
+                "Go" => Some(Event {
+                    description: "Golang".to_string(),
+                    ..event.clone()
+                }),
+                _ => Some(event.clone()),
+            }
+        })
+        .unwrap();
+
+        let written = std::fs::read_to_string(&events_path).unwrap();
+        std::fs::remove_dir_all(&directory).unwrap();
+        assert_eq!(
+            written,
+            format!(
+                "date,category,description\n2023-05-09,school,Golang\nnot a date,x,Kept\n{}\n",
+                notes_row
+            )
+        );
+        assert!(!temp_path.exists());
+    }
 }
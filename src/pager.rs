@@ -0,0 +1,88 @@
+use std::env;
+use std::fs::File;
+use std::io::{IsTerminal, Write};
+use std::process::{self, Command, Stdio};
+
+/// Print the lines, through $PAGER if they don't fit on the terminal. less is used if
+/// PAGER isn't set and an empty PAGER turns paging off. When stdout isn't a terminal,
+/// like in pipes and scripts, the lines are always printed as they are
+pub fn print_lines(lines: &[String]) {
+    if let Some(pager) = pager(lines.len()) {
+        if run_pager(&pager, lines) {
+            return;
+        }
+    }
+    // A closed pipe, like in days list | head, ends the output without the rest
+    let mut stdout = std::io::stdout().lock();
+    for line in lines {
+        if writeln!(stdout, "{}", line).is_err() {
+            process::exit(0);
+        }
+    }
+}
+
+/// The pager command if the lines don't fit on the terminal with the prompt
+fn pager(line_count: usize) -> Option<String> {
+    if !std::io::stdout().is_terminal() {
+        return None;
+    }
+    let pager = env::var("PAGER").unwrap_or_else(|_| "less".to_string());
+    if pager.trim().is_empty() || line_count < terminal_height()? {
+        return None;
+    }
+    Some(pager)
+}
+
+/// Rows of the terminal from LINES or stty, None if they can't be found out like on Windows
+fn terminal_height() -> Option<usize> {
+    if let Some(lines) = env::var("LINES").ok().and_then(|lines| lines.parse().ok()) {
+        return Some(lines);
+    }
+    // stty tells the size of the terminal it gets as stdin
+    let tty = File::open("/dev/tty").ok()?;
+    let output = Command::new("stty")
+        .arg("size")
+        .stdin(tty)
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    String::from_utf8(output.stdout)
+        .ok()?
+        .split_whitespace()
+        .next()?
+        .parse()
+        .ok()
+}
+
+/// Run the pager with the shell and write the lines to it. Returns false if the pager
+/// couldn't be started or wasn't found, so the lines can be printed without it
+fn run_pager(pager: &str, lines: &[String]) -> bool {
+    let mut command = if cfg!(windows) {
+        let mut command = Command::new("cmd");
+        command.args(["/C", pager]);
+        command
+    } else {
+        let mut command = Command::new("sh");
+        command.args(["-c", pager]);
+        command
+    };
+    // Like git, less keeps the colors and quits by itself if everything fits after all
+    if env::var_os("LESS").is_none() {
+        command.env("LESS", "FRX");
+    }
+    let mut child = match command.stdin(Stdio::piped()).spawn() {
+        Ok(child) => child,
+        Err(_) => return false,
+    };
+
+    // Quitting the pager early closes its stdin, which isn't an error
+    if let Some(mut stdin) = child.stdin.take() {
+        for line in lines {
+            if writeln!(stdin, "{}", line).is_err() {
+                break;
+            }
+        }
+    }
+    // The shell exits with 127 if there's no such command
+    !matches!(child.wait(), Ok(status) if status.code() == Some(127))
+}
@@ -0,0 +1,238 @@
+use chrono::{Datelike, Months, NaiveDate};
+
+use crate::anniversary;
+use crate::config;
+use crate::date_offset::DateOffset;
+
+/// How precisely the date of an event is known. The event's date is the day the event is
+/// sorted and filtered by: the first day of the year or month, or for year-less dates the
+/// next time the month and day come, today included
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Precision {
+    /// 2023-05-10
+    Day,
+    /// 2023-05
+    Month,
+    /// 1985
+    Year,
+    /// --05-10, without a year like birthdays. The month and day are kept since
+    /// February 29 can fall on another day
+    MonthDay { month: u32, day: u32 },
+}
+
+/// Parse a date of events.csv or --date with its precision: 2023-05-10, 2023-05, 1985 or --05-10.
+/// Year-less dates are put on their next time from today
+pub fn parse(date: &str, today: NaiveDate) -> Option<(NaiveDate, Precision)> {
+    let date = date.trim();
+    if let Ok(day) = NaiveDate::parse_from_str(date, "%Y-%m-%d") {
+        return Some((day, Precision::Day));
+    }
+    if let Some(month_day) = date.strip_prefix("--") {
+        // Checked against a leap year so --02-29 is allowed
+        let day = NaiveDate::parse_from_str(&format!("2000-{}", month_day), "%Y-%m-%d").ok()?;
+        let (next, _) = anniversary::next(day, today)?;
+        let precision = Precision::MonthDay {
+            month: day.month(),
+            day: day.day(),
+        };
+        return Some((next, precision));
+    }
+    // Only four digit years, so offsets and other numbers aren't taken as years
+    let digits = |part: &str, length: usize| {
+        part.len() == length && part.chars().all(|c| c.is_ascii_digit())
+    };
+    match date.split('-').collect::<Vec<&str>>().as_slice() {
+        [year, month] if digits(year, 4) && digits(month, 2) => {
+            let first = NaiveDate::from_ymd_opt(year.parse().ok()?, month.parse().ok()?, 1)?;
+            Some((first, Precision::Month))
+        }
+        [year] if digits(year, 4) => {
+            let first = NaiveDate::from_ymd_opt(year.parse().ok()?, 1, 1)?;
+            Some((first, Precision::Year))
+        }
+        _ => None,
+    }
+}
+
+impl Precision {
+    /// Whether the date has a day, so it can be put on a calendar and reminded of.
+    /// Months and years are only sorted and filtered
+    pub fn has_day(&self) -> bool {
+        matches!(self, Precision::Day | Precision::MonthDay { .. })
+    }
+
+    /// The first and the last day the date covers, a single day unless it's a month or a year
+    pub fn span(&self, date: NaiveDate) -> (NaiveDate, NaiveDate) {
+        let last = match self {
+            Precision::Month => date
+                .checked_add_months(Months::new(1))
+                .and_then(|next| next.pred_opt()),
+            Precision::Year => NaiveDate::from_ymd_opt(date.year(), 12, 31),
+            _ => None,
+        };
+        (date, last.unwrap_or(date))
+    }
+
+    /// Whether the offset moves a date of this precision to another date. Days, weeks and
+    /// working days can't move months and years and whole years don't move year-less dates
+    pub fn can_shift_by(&self, offset: DateOffset) -> bool {
+        match (self, offset) {
+            (Precision::Day, _) => true,
+            (Precision::Month, DateOffset::Months(_) | DateOffset::Years(_)) => true,
+            (Precision::Year, DateOffset::Years(_)) => true,
+            (Precision::Year, DateOffset::Months(months)) => months % 12 == 0,
+            (Precision::MonthDay { .. }, DateOffset::Years(_)) => false,
+            (Precision::MonthDay { .. }, DateOffset::Months(months)) => months % 12 != 0,
+            (Precision::MonthDay { .. }, _) => true,
+            _ => false,
+        }
+    }
+
+    /// The date moved by the offset and its precision, a year-less date gets the new
+    /// month and day. None if the date goes out of range
+    pub fn shift(&self, date: NaiveDate, offset: DateOffset) -> Option<(NaiveDate, Precision)> {
+        let shifted = offset.apply(date)?;
+        let precision = match self {
+            Precision::MonthDay { .. } => Precision::MonthDay {
+                month: shifted.month(),
+                day: shifted.day(),
+            },
+            _ => *self,
+        };
+        Some((shifted, precision))
+    }
+
+    /// The name used in the JSON output
+    pub fn name(&self) -> &'static str {
+        match self {
+            Precision::Day => "day",
+            Precision::Month => "month",
+            Precision::Year => "year",
+            Precision::MonthDay { .. } => "month-day",
+        }
+    }
+
+    /// The date like it's stored in events.csv
+    pub fn format(&self, date: NaiveDate) -> String {
+        match self {
+            Precision::Day => date.format("%Y-%m-%d").to_string(),
+            _ => self.display(date),
+        }
+    }
+
+    /// The date for printing, full dates in the date_format of the config
+    pub fn display(&self, date: NaiveDate) -> String {
+        match self {
+            Precision::Day => date.format(&config::get().date_format).to_string(),
+            Precision::Month => date.format("%Y-%m").to_string(),
+            Precision::Year => date.format("%Y").to_string(),
+            Precision::MonthDay { month, day } => format!("--{:02}-{:02}", month, day),
+        }
+    }
+
+    /// The delta from today in the precision of the date, like in 2 years or last month.
+    /// None for days, year-less dates included, they have the usual deltas
+    pub fn delta(&self, date: NaiveDate, today: NaiveDate) -> Option<String> {
+        let locale = config::get().locale;
+        match self {
+            Precision::Day | Precision::MonthDay { .. } => None,
+            Precision::Month => {
+                let months =
+                    (date.year() - today.year()) * 12 + date.month() as i32 - today.month() as i32;
+                Some(locale.months_delta(months))
+            }
+            Precision::Year => Some(locale.years_delta(date.year() - today.year())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::date;
+
+    fn today() -> NaiveDate {
+        date(2023, 5, 10)
+    }
+
+    #[test]
+    fn parses_every_precision() {
+        assert_eq!(
+            parse("2023-05-11", today()),
+            Some((date(2023, 5, 11), Precision::Day))
+        );
+        assert_eq!(
+            parse("2023-06", today()),
+            Some((date(2023, 6, 1), Precision::Month))
+        );
+        assert_eq!(
+            parse("1985", today()),
+            Some((date(1985, 1, 1), Precision::Year))
+        );
+        assert_eq!(
+            parse("--05-20", today()),
+            Some((date(2023, 5, 20), Precision::MonthDay { month: 5, day: 20 }))
+        );
+    }
+
+    #[test]
+    fn year_less_dates_are_on_their_next_day() {
+        assert_eq!(parse("--05-10", today()).map(|p| p.0), Some(today()));
+        assert_eq!(
+            parse("--05-01", today()).map(|p| p.0),
+            Some(date(2024, 5, 1))
+        );
+        assert_eq!(
+            parse("--02-29", date(2024, 1, 10)).map(|p| p.0),
+            Some(date(2024, 2, 29))
+        );
+    }
+
+    #[test]
+    fn rejects_bad_dates() {
+        for bad in [
+            "2023-13", "--13-01", "--02-30", "05-10", "12345", "85", "+3d", "",
+        ] {
+            assert_eq!(parse(bad, today()), None, "{}", bad);
+        }
+    }
+
+    #[test]
+    fn formats_like_they_are_stored() {
+        for stored in ["2023-05-11", "2023-06", "1985", "--05-20", "--02-29"] {
+            let (date, precision) = parse(stored, date(2024, 1, 10)).unwrap();
+            assert_eq!(precision.format(date), stored);
+        }
+    }
+
+    #[test]
+    fn spans_cover_months_and_years() {
+        assert_eq!(
+            Precision::Month.span(date(2024, 2, 1)),
+            (date(2024, 2, 1), date(2024, 2, 29))
+        );
+        assert_eq!(
+            Precision::Year.span(date(1985, 1, 1)),
+            (date(1985, 1, 1), date(1985, 12, 31))
+        );
+        assert_eq!(Precision::Day.span(today()), (today(), today()));
+    }
+
+    #[test]
+    fn shifts_in_the_precision() {
+        assert!(!Precision::Month.can_shift_by(DateOffset::Days(3)));
+        assert!(Precision::Month.can_shift_by(DateOffset::Months(1)));
+        assert!(!Precision::Year.can_shift_by(DateOffset::Months(2)));
+        assert!(Precision::Year.can_shift_by(DateOffset::Months(-24)));
+        let birthday = Precision::MonthDay { month: 5, day: 20 };
+        assert!(!birthday.can_shift_by(DateOffset::Years(1)));
+        assert_eq!(
+            birthday.shift(date(2023, 5, 20), DateOffset::Days(12)),
+            Some((date(2023, 6, 1), Precision::MonthDay { month: 6, day: 1 }))
+        );
+        assert_eq!(
+            Precision::Month.shift(date(2023, 6, 1), DateOffset::Months(-7)),
+            Some((date(2022, 11, 1), Precision::Month))
+        );
+    }
+}
@@ -0,0 +1,138 @@
+use chrono::NaiveDate;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::process;
+use std::time::UNIX_EPOCH;
+
+use crate::config;
+use crate::{fill_template, home_directory, read_events, Event};
+
+/// ~/.cache/days/prompt, or under XDG_CACHE_HOME if it's set
+fn cache_path() -> PathBuf {
+    let cache_directory = match env::var("XDG_CACHE_HOME") {
+        Ok(directory) if !directory.is_empty() => PathBuf::from(directory),
+        _ => home_directory().join(".cache"),
+    };
+    cache_directory.join("days").join("prompt")
+}
+
+/// What the output depends on: the events file with its modification time and size,
+/// today and the settings used. The output is cached until one of these changes
+fn cache_key(template: &str, today: NaiveDate) -> Option<String> {
+    let config = config::get();
+    let metadata = fs::metadata(&config.data_file).ok()?;
+    let modified = metadata.modified().ok()?.duration_since(UNIX_EPOCH).ok()?;
+    Some(format!(
+        "{:?} {} {} {} {:?} {:?} {:?}",
+        config.data_file,
+        modified.as_nanos(),
+        metadata.len(),
+        today,
+        template,
+        config.date_format,
+        config.locale
+    ))
+}
+
+/// The cached output if the key matches, the first line of the cache is the key
+fn read_cache(key: &str) -> Option<String> {
+    let cache = fs::read_to_string(cache_path()).ok()?;
+    let (cached_key, output) = cache.split_once('\n')?;
+    (cached_key == key).then(|| output.to_string())
+}
+
+/// The cache is only for speed, so failing to write it isn't an error
+fn write_cache(key: &str, output: &str) {
+    let path = cache_path();
+    if let Some(directory) = path.parent() {
+        let _ = fs::create_dir_all(directory);
+    }
+    let _ = fs::write(path, format!("{}\n{}", key, output));
+}
+
+/// Fill the prompt template from the events of today and the next day with events:
+///
+/// {today} descriptions of today's events, {today_count} the number of them,
+/// {next} description of the next event, {next_date}, {next_days} and {next_delta}
+///
+/// The result is empty if there are no events today or later
+pub fn render(template: &str, events: &[Event], today: NaiveDate) -> String {
+    let config = config::get();
+    let today_events: Vec<&str> = events
+        .iter()
+        .filter(|e| e.precision.has_day() && e.date == today)
+        .map(|e| e.description.as_str())
+        .collect();
+    let next = events
+        .iter()
+        .filter(|e| e.precision.has_day() && e.date > today)
+        .min_by_key(|e| e.date);
+    if today_events.is_empty() && next.is_none() {
+        return String::new();
+    }
+
+    let (next_description, next_date, next_days, next_delta) = match next {
+        Some(next) => {
+            let days = (next.date - today).num_days();
+            (
+                next.description.clone(),
+                next.date.format(&config.date_format).to_string(),
+                days.to_string(),
+                config.locale.delta(days),
+            )
+        }
+        None => Default::default(),
+    };
+
+    fill_template(
+        template,
+        &[
+            ("today", &today_events.join(", ")),
+            ("today_count", &today_events.len().to_string()),
+            ("next", &next_description),
+            ("next_date", &next_date),
+            ("next_days", &next_days),
+            ("next_delta", &next_delta),
+        ],
+    )
+}
+
+/// The prompt command, [--template <template>]. The output is a single line without colors
+pub fn run_prompt_command(args: &[String], today: NaiveDate) {
+    let arg_template = "--template";
+    let mut template = config::get().prompt_template.clone();
+    let mut i = 0;
+    while i < args.len() {
+        if args[i] == arg_template && i + 1 < args.len() {
+            template = args[i + 1].clone();
+            i += 2;
+            continue;
+        }
+        eprintln!("Unknown option: {}", args[i]);
+        process::exit(1);
+    }
+
+    let key = cache_key(&template, today);
+    if let Some(output) = key.as_deref().and_then(read_cache) {
+        if !output.is_empty() {
+            println!("{}", output);
+        }
+        return;
+    }
+
+    let events = match read_events(&config::get().data_file) {
+        Ok(events) => events,
+        Err(e) => {
+            eprintln!("Error reading events.csv file: {}", e);
+            process::exit(1);
+        }
+    };
+    let output = render(&template, &events, today);
+    if let Some(key) = key {
+        write_cache(&key, &output);
+    }
+    if !output.is_empty() {
+        println!("{}", output);
+    }
+}
@@ -0,0 +1,184 @@
+use chrono::NaiveDate;
+use std::collections::BTreeSet;
+use std::fs::{self, OpenOptions};
+use std::io::Error;
+use std::path::PathBuf;
+use std::process::{self, Command};
+
+use crate::config;
+use crate::date_offset::DateOffset;
+use crate::{format_delta, Event};
+
+/// A reminder that has fired, the event date, category, description and the lead time
+type Fired = (String, String, String, String);
+
+/// The reminders_file from the config, or reminders.csv next to events.csv
+fn state_path() -> PathBuf {
+    let config = config::get();
+    match &config.reminders_file {
+        Some(path) => path.clone(),
+        None => config
+            .data_file
+            .parent()
+            .map(|directory| directory.join("reminders.csv"))
+            .unwrap_or_else(|| PathBuf::from("reminders.csv")),
+    }
+}
+
+/// Check the lead time of a reminder, like 7d or 2w
+pub fn parse_lead_time(lead: &str) -> Option<DateOffset> {
+    match DateOffset::parse(lead)? {
+        DateOffset::Days(n)
+        | DateOffset::Weeks(n)
+        | DateOffset::Months(n)
+        | DateOffset::Years(n)
+        | DateOffset::WorkingDays(n)
+            if n < 0 =>
+        {
+            None
+        }
+        offset => Some(offset),
+    }
+}
+
+fn fired_key(event: &Event, lead: &str) -> Fired {
+    (
+        event.date.format("%Y-%m-%d").to_string(),
+        event.category.clone(),
+        event.description.clone(),
+        lead.to_string(),
+    )
+}
+
+/// Read the reminders that have already fired, a missing file means none have
+fn read_state(path: &PathBuf) -> Result<BTreeSet<Fired>, Error> {
+    let mut fired = BTreeSet::new();
+    let csv = match fs::read_to_string(path) {
+        Ok(csv) => csv,
+        Err(_) => return Ok(fired),
+    };
+    let mut rdr = csv::ReaderBuilder::new()
+        .has_headers(true)
+        .flexible(true)
+        .from_reader(csv.as_bytes());
+    for result in rdr.records() {
+        let record = result?;
+        let field = |i: usize| record.get(i).unwrap_or("").to_string();
+        fired.insert((field(0), field(1), field(2), field(3)));
+    }
+    Ok(fired)
+}
+
+/// Write the fired reminders, leaving out the ones of past events as they can't fire again
+fn write_state(path: &PathBuf, fired: &BTreeSet<Fired>, today: NaiveDate) -> Result<(), Error> {
+    let file = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(path)?;
+    let mut writer = csv::Writer::from_writer(file);
+    writer.write_record(["date", "category", "description", "lead"])?;
+    let today = today.format("%Y-%m-%d").to_string();
+    for (date, category, description, lead) in fired.iter().filter(|f| f.0 >= today) {
+        writer.write_record([date, category, description, lead])?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// The reminder as plain text, like 2023-05-17: Release (work) - in 7 days
+fn message(event: &Event, today: NaiveDate) -> String {
+    let config = config::get();
+    let delta = (event.date - today).num_days();
+    let date = event.date.format(&config.date_format);
+    if event.category.is_empty() {
+        format!("{}: {} - {}", date, event.description, format_delta(&delta))
+    } else {
+        format!(
+            "{}: {} ({}) - {}",
+            date,
+            event.description,
+            event.category,
+            format_delta(&delta)
+        )
+    }
+}
+
+/// Run the command with the message as the last argument and the event in DAYS_* variables.
+/// The command isn't run through a shell, so the event text can't run anything
+fn run_exec(exec: &[String], event: &Event, message: &str) -> bool {
+    let status = Command::new(&exec[0])
+        .args(&exec[1..])
+        .arg(message)
+        .env("DAYS_DATE", event.date.format("%Y-%m-%d").to_string())
+        .env("DAYS_CATEGORY", &event.category)
+        .env("DAYS_DESCRIPTION", &event.description)
+        .status();
+    match status {
+        Ok(status) if status.success() => true,
+        Ok(status) => {
+            eprintln!("{} failed: {}", exec[0], status);
+            false
+        }
+        Err(e) => {
+            eprintln!("Error running {}: {}", exec[0], e);
+            false
+        }
+    }
+}
+
+/// Print the reminders that are due and haven't fired yet, and remember them so each
+/// fires once. A reminder is due from the lead time before the event until the event.
+/// With exec the command is run for each reminder, a failed one fires again the next time
+pub fn remind(events: &[Event], today: NaiveDate, exec: Option<&[String]>, dry_run: bool) {
+    let path = state_path();
+    let mut fired = match read_state(&path) {
+        Ok(fired) => fired,
+        Err(e) => {
+            eprintln!("Error reading {}: {}", path.display(), e);
+            process::exit(1);
+        }
+    };
+
+    // Months and years have no day to remind of
+    for event in events
+        .iter()
+        .filter(|e| e.precision.has_day() && e.date >= today)
+    {
+        // Leads that are due and haven't fired, like 7d and 1d if the reminders were missed
+        let due: Vec<&String> = event
+            .remind
+            .iter()
+            .filter(|lead| {
+                parse_lead_time(lead)
+                    .and_then(|offset| offset.negated().apply(event.date))
+                    .is_some_and(|remind_date| remind_date <= today)
+            })
+            .filter(|lead| !fired.contains(&fired_key(event, lead)))
+            .collect();
+        if due.is_empty() {
+            continue;
+        }
+
+        let message = message(event, today);
+        println!("{}", message);
+        if dry_run {
+            continue;
+        }
+        if let Some(exec) = exec {
+            if !run_exec(exec, event, &message) {
+                continue;
+            }
+        }
+        for lead in due {
+            fired.insert(fired_key(event, lead));
+        }
+    }
+
+    if !dry_run {
+        if let Err(e) = write_state(&path, &fired, today) {
+            eprintln!("Error writing {}: {}", path.display(), e);
+            process::exit(1);
+        }
+    }
+}
@@ -0,0 +1,208 @@
+use regex::{Regex, RegexBuilder};
+use std::process;
+
+/// How the search text is matched against event descriptions
+#[derive(Debug, PartialEq)]
+enum SearchMode {
+    /// The text appears somewhere in the description
+    Substring,
+    /// The text is a regular expression
+    Regex,
+    /// The characters of the text appear in order, like "crs" matches "Starting course work"
+    Fuzzy,
+}
+
+/// Search for event descriptions, given with --search <text> and optionally
+/// --ignore-case, --regex or --fuzzy
+#[derive(Debug)]
+pub struct Search {
+    text: String,
+    /// Substring and regex searches are both done with a regex, None means fuzzy search
+    regex: Option<Regex>,
+}
+
+impl Search {
+    /// Take the search options out of the options. Returns the search, if --search was given,
+    /// and the rest of the options
+    pub fn from_options(options: &[String]) -> (Option<Search>, Vec<String>) {
+        let arg_search = "--search";
+        let arg_ignore_case = "--ignore-case";
+        let arg_regex = "--regex";
+        let arg_fuzzy = "--fuzzy";
+
+        let mut text = None;
+        let mut ignore_case = false;
+        let mut mode = SearchMode::Substring;
+        let mut rest = Vec::new();
+
+        let mut i = 0;
+        while i < options.len() {
+            if options[i] == arg_search {
+                if i + 1 >= options.len() {
+                    eprintln!("No search text given");
+                    process::exit(1);
+                }
+                text = Some(options[i + 1].clone());
+                i += 2;
+                continue;
+            }
+
+            if options[i] == arg_ignore_case {
+                ignore_case = true;
+            } else if options[i] == arg_regex {
+                mode = SearchMode::Regex;
+            } else if options[i] == arg_fuzzy {
+                mode = SearchMode::Fuzzy;
+            } else {
+                rest.push(options[i].clone());
+            }
+            i += 1;
+        }
+
+        let text = match text {
+            Some(text) => text,
+            None => return (None, rest),
+        };
+
+        let regex = match mode {
+            SearchMode::Substring => Some(build_regex(&regex::escape(&text), ignore_case)),
+            SearchMode::Regex => Some(build_regex(&text, ignore_case)),
+            SearchMode::Fuzzy => None,
+        };
+
+        (Some(Search { text, regex }), rest)
+    }
+
+    pub fn is_match(&self, description: &str) -> bool {
+        !self.find(description).is_empty()
+    }
+
+    /// Byte ranges of the matched parts of the description, empty if it doesn't match
+    pub fn find(&self, description: &str) -> Vec<(usize, usize)> {
+        if let Some(regex) = &self.regex {
+            return regex
+                .find_iter(description)
+                .filter(|m| !m.as_str().is_empty())
+                .map(|m| (m.start(), m.end()))
+                .collect();
+        }
+        fuzzy_find(&self.text, description)
+    }
+
+    /// Wrap the matched parts of the description between start and end, like ANSI color codes
+    pub fn highlight(&self, description: &str, start: &str, end: &str) -> String {
+        let mut highlighted = String::new();
+        let mut position = 0;
+        for (match_start, match_end) in self.find(description) {
+            highlighted.push_str(&description[position..match_start]);
+            highlighted.push_str(start);
+            highlighted.push_str(&description[match_start..match_end]);
+            highlighted.push_str(end);
+            position = match_end;
+        }
+        highlighted.push_str(&description[position..]);
+        highlighted
+    }
+}
+
+fn build_regex(pattern: &str, ignore_case: bool) -> Regex {
+    match RegexBuilder::new(pattern)
+        .case_insensitive(ignore_case)
+        .build()
+    {
+        Ok(regex) => regex,
+        Err(e) => {
+            eprintln!("Bad search given: {}", e);
+            process::exit(1);
+        }
+    }
+}
+
+/// Find the characters of the text in order from the description, ignoring case.
+/// Returns the byte range of every matched character, or nothing if some character is missing
+fn fuzzy_find(text: &str, description: &str) -> Vec<(usize, usize)> {
+    let mut wanted = text.chars().filter(|c| !c.is_whitespace()).peekable();
+    let mut found = Vec::new();
+
+    for (index, c) in description.char_indices() {
+        match wanted.peek() {
+            Some(w) if w.to_lowercase().eq(c.to_lowercase()) => {
+                found.push((index, index + c.len_utf8()));
+                wanted.next();
+            }
+            Some(_) => {}
+            None => break,
+        }
+    }
+
+    if wanted.peek().is_some() {
+        return Vec::new();
+    }
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn search(options: &str) -> Search {
+        let options: Vec<String> = options.split_whitespace().map(String::from).collect();
+        Search::from_options(&options).0.unwrap()
+    }
+
+    #[test]
+    fn takes_the_search_options_out() {
+        let options: Vec<String> = ["--today", "--search", "go", "--fuzzy", "--ignore-case"]
+            .iter()
+            .map(|o| o.to_string())
+            .collect();
+        let (found, rest) = Search::from_options(&options);
+        assert!(found.is_some());
+        assert_eq!(rest, vec!["--today".to_string()]);
+
+        let (found, rest) = Search::from_options(&rest);
+        assert!(found.is_none());
+        assert_eq!(rest, vec!["--today".to_string()]);
+    }
+
+    #[test]
+    fn matches_substrings() {
+        assert!(search("--search course").is_match("Starting course work"));
+        assert!(!search("--search Course").is_match("Starting course work"));
+        assert!(search("--search Course --ignore-case").is_match("Starting course work"));
+        // The text isn't a pattern without --regex
+        assert!(!search("--search c.urse").is_match("Starting course work"));
+    }
+
+    #[test]
+    fn matches_regular_expressions() {
+        let regex = search("--search ^start.*work$ --regex --ignore-case");
+        assert!(regex.is_match("Starting course work"));
+        assert!(!regex.is_match("Starting course"));
+        assert_eq!(
+            search("--search o --regex").find("Go to go"),
+            vec![(1, 2), (4, 5), (7, 8)]
+        );
+    }
+
+    #[test]
+    fn matches_fuzzy_in_order() {
+        let fuzzy = search("--search crs --fuzzy");
+        assert!(fuzzy.is_match("Starting course work"));
+        assert!(!search("--search src --fuzzy").is_match("course"));
+        assert_eq!(fuzzy.find("Course"), vec![(0, 1), (3, 4), (4, 5)]);
+        // Multibyte characters are matched whole
+        assert_eq!(
+            search("--search äö --fuzzy").find("Ääliö"),
+            vec![(0, 2), (6, 8)]
+        );
+    }
+
+    #[test]
+    fn highlights_the_matches() {
+        assert_eq!(
+            search("--search go --ignore-case").highlight("Go to go", "[", "]"),
+            "[Go] to [go]"
+        );
+    }
+}
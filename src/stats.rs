@@ -0,0 +1,157 @@
+use chrono::{Datelike, NaiveDate};
+use serde_json::json;
+use std::collections::BTreeMap;
+
+use crate::categories;
+use crate::precision::Precision;
+use crate::Event;
+
+/// How many of the busiest days are shown
+const BUSIEST_DAYS: usize = 5;
+
+/// Counts of the events per category, month and year
+#[derive(Debug)]
+pub struct Stats<'a> {
+    total: usize,
+    /// Events without a category are counted separately
+    per_category: BTreeMap<String, usize>,
+    uncategorized: usize,
+    /// YYYY-MM
+    per_month: BTreeMap<String, usize>,
+    per_year: BTreeMap<i32, usize>,
+    first: Option<&'a Event>,
+    last: Option<&'a Event>,
+    /// Days with the most events, most first
+    busiest_days: Vec<(NaiveDate, usize)>,
+}
+
+impl<'a> Stats<'a> {
+    pub fn new(events: &[&'a Event]) -> Self {
+        let mut per_category = BTreeMap::new();
+        let mut uncategorized = 0;
+        let mut per_month = BTreeMap::new();
+        let mut per_year = BTreeMap::new();
+        let mut per_day: BTreeMap<NaiveDate, usize> = BTreeMap::new();
+
+        for event in events {
+            if event.category.is_empty() {
+                uncategorized += 1;
+            } else {
+                // Aliases are counted under the registered name
+                let category = categories::get().canonical(&event.category);
+                *per_category.entry(category).or_insert(0) += 1;
+            }
+            // Only the dates that have a year, a month or a day are counted in them
+            match event.precision {
+                Precision::Day => {
+                    *per_month
+                        .entry(event.date.format("%Y-%m").to_string())
+                        .or_insert(0) += 1;
+                    *per_year.entry(event.date.year()).or_insert(0) += 1;
+                    *per_day.entry(event.date).or_insert(0) += 1;
+                }
+                Precision::Month => {
+                    *per_month
+                        .entry(event.date.format("%Y-%m").to_string())
+                        .or_insert(0) += 1;
+                    *per_year.entry(event.date.year()).or_insert(0) += 1;
+                }
+                Precision::Year => *per_year.entry(event.date.year()).or_insert(0) += 1,
+                Precision::MonthDay { .. } => {}
+            }
+        }
+        // Year-less dates are on their next day, so they're neither first nor last
+        let dated = events
+            .iter()
+            .copied()
+            .filter(|e| !matches!(e.precision, Precision::MonthDay { .. }));
+
+        // Most events first, earlier dates first on ties
+        let mut busiest_days: Vec<(NaiveDate, usize)> = per_day.into_iter().collect();
+        busiest_days.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        busiest_days.truncate(BUSIEST_DAYS);
+
+        Self {
+            total: events.len(),
+            per_category,
+            uncategorized,
+            per_month,
+            per_year,
+            first: dated.clone().min_by_key(|e| e.date),
+            last: dated.max_by_key(|e| e.date),
+            busiest_days,
+        }
+    }
+
+    pub fn total(&self) -> usize {
+        self.total
+    }
+
+    /// Print the stats as text tables
+    pub fn print_table(&self) {
+        println!("Events: {}", self.total);
+        if let (Some(first), Some(last)) = (self.first, self.last) {
+            println!(
+                "First:  {}: {} ({})",
+                first.precision.format(first.date),
+                first.description,
+                first.category
+            );
+            println!(
+                "Last:   {}: {} ({})",
+                last.precision.format(last.date),
+                last.description,
+                last.category
+            );
+        }
+
+        println!();
+        println!("{:<20}{:>8}", "Category", "Events");
+        for (category, count) in self.per_category.iter() {
+            println!("{:<20}{:>8}", category, count);
+        }
+        if self.uncategorized > 0 {
+            println!("{:<20}{:>8}", "(no category)", self.uncategorized);
+        }
+
+        println!();
+        println!("{:<20}{:>8}", "Year", "Events");
+        for (year, count) in self.per_year.iter() {
+            println!("{:<20}{:>8}", year, count);
+        }
+
+        println!();
+        println!("{:<20}{:>8}", "Month", "Events");
+        for (month, count) in self.per_month.iter() {
+            println!("{:<20}{:>8}", month, count);
+        }
+
+        println!();
+        println!("{:<20}{:>8}", "Busiest days", "Events");
+        for (date, count) in self.busiest_days.iter() {
+            println!("{:<20}{:>8}", date.format("%Y-%m-%d").to_string(), count);
+        }
+    }
+
+    /// The stats as JSON, for charting and scripts
+    pub fn to_json(&self) -> serde_json::Value {
+        let event_json = |event: Option<&Event>| match event {
+            Some(event) => event.to_json(),
+            None => serde_json::Value::Null,
+        };
+
+        json!({
+            "total": self.total,
+            "categories": self.per_category,
+            "uncategorized": self.uncategorized,
+            "years": self.per_year.iter().map(|(year, count)| (year.to_string(), *count)).collect::<BTreeMap<String, usize>>(),
+            "months": self.per_month,
+            "first": event_json(self.first),
+            "last": event_json(self.last),
+            "busiest_days": self.busiest_days.iter().map(|(date, count)| json!({
+                "date": date.format("%Y-%m-%d").to_string(),
+                "events": count,
+            })).collect::<Vec<_>>(),
+        })
+    }
+}